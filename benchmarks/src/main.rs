@@ -1,7 +1,152 @@
-use grid::Grid;
-use std::cmp::{max, min};
+use grid::heuristic::{Chebyshev, Euclidean, Manhattan};
+use grid::{Grid, Heuristic, SearchResult};
+use rand::{thread_rng, Rng};
+use std::cmp::Reverse;
+use std::env;
+use std::path::Path;
 use std::time::Instant;
+mod scenarios;
+
+type Algorithm = fn(&Grid, (usize, usize), (usize, usize), &dyn Heuristic) -> SearchResult;
+
+//the algorithms and the heuristics go with their names, so the slowest maps can say how they were searched
+type NamedAlgorithm = (&'static str, Algorithm);
+type NamedHeuristic = (&'static str, &'static dyn Heuristic);
+
+const A_STAR: NamedAlgorithm = ("A*", a_star);
+const ALGORITHMS: [NamedAlgorithm; 3] = [A_STAR, ("JPS", jump_point), ("Bidir", bidirectional)];
+
+const MANHATTAN: NamedHeuristic = ("Manhattan", &Manhattan);
+const EUCLIDEAN: NamedHeuristic = ("Euclidean", &Euclidean);
+const CHEBYSHEV: NamedHeuristic = ("Chebyshev", &Chebyshev);
+
+//the random maps of the benchmarks, so the slowest ones can be generated again
+//with Grid::fill_random_solvable_with_seed and the same size and percentage of walls
+struct Maps {
+    next_seed: u64,
+    slowest: Vec<SlowCase>,
+    attempts: Vec<Attempts>,
+}
+
+struct SlowCase {
+    micros: u128,
+    size: (usize, usize),
+    wall_percentage: usize,
+    seed: u64,
+    algorithm: &'static str,
+    heuristic: &'static str,
+}
+
+//how many maps had to be generated to get the solvable ones
+//the more are discarded, the more the solvable ones differ from the rest
+struct Attempts {
+    size: (usize, usize),
+    wall_percentage: usize,
+    solvable: usize,
+    generated: usize,
+}
+
+const N_SLOWEST: usize = 10;
+
+const USAGE: &str = "Usage: benchmarks [--seed <seed>] | benchmarks --replay <seed> <rows> <columns> <wall percentage> | benchmarks [--scen] <file.scen> [maps directory]";
+
+impl Maps {
+    fn new(first_seed: u64) -> Self {
+        Maps {
+            next_seed: first_seed,
+            slowest: Vec::new(),
+            attempts: Vec::new(),
+        }
+    }
+
+    //fills the grid with a map with a path from the car to the goal and returns its seed
+    fn generate(&mut self, grid: &mut Grid, wall_percentage: usize) -> u64 {
+        let seed = self.next_seed;
+        self.next_seed = self.next_seed.wrapping_add(1);
+        let generated = grid
+            .fill_random_solvable_with_seed(wall_percentage, seed)
+            .expect("Couldn't generate a map with a path");
+        let size = (grid.m(), grid.n());
+        match self
+            .attempts
+            .iter_mut()
+            .find(|attempts| attempts.size == size && attempts.wall_percentage == wall_percentage)
+        {
+            Some(attempts) => {
+                attempts.solvable += 1;
+                attempts.generated += generated;
+            }
+            None => self.attempts.push(Attempts {
+                size,
+                wall_percentage,
+                solvable: 1,
+                generated,
+            }),
+        }
+        seed
+    }
+
+    fn record(&mut self, case: SlowCase) {
+        if self.slowest.len() < N_SLOWEST || case.micros > self.slowest[N_SLOWEST - 1].micros {
+            self.slowest.push(case);
+            self.slowest.sort_by_key(|case| Reverse(case.micros));
+            self.slowest.truncate(N_SLOWEST);
+        }
+    }
+}
+
 fn main() {
+    //benchmarks [--scen] <file.scen> [maps directory] runs the scenarios of the file instead
+    //benchmarks --seed <seed> runs the same maps as a previous run
+    //benchmarks --replay <seed> <rows> <columns> <wall percentage> runs only one of its maps
+    let args: Vec<String> = env::args().skip(1).collect();
+    let first_seed = match args.as_slice() {
+        [] => thread_rng().gen(),
+        [flag, seed] if flag == "--seed" => match seed.parse() {
+            Ok(seed) => seed,
+            Err(_) => {
+                eprintln!("The --seed parameter must be a positive integer");
+                std::process::exit(1);
+            }
+        },
+        [flag, case @ ..] if flag == "--replay" && case.len() == 4 => {
+            match (
+                case[0].parse(),
+                case[1].parse(),
+                case[2].parse(),
+                case[3].parse(),
+            ) {
+                (Ok(seed), Ok(m @ 1..=usize::MAX), Ok(n @ 1..=usize::MAX), Ok(walls @ 0..=100)) => {
+                    replay(seed, m, n, walls)
+                }
+                _ => {
+                    eprintln!("The --replay parameters must be a seed, the rows, the columns and the percentage of walls");
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
+        [flag] if flag == "--help" => {
+            println!("{}", USAGE);
+            return;
+        }
+        //the path can't be taken for an unknown flag, it's given with --scen or ends in .scen
+        [flag, scen_path, rest @ ..] if flag == "--scen" && is_maps_dir(rest) => {
+            run_scenarios(scen_path, rest.first());
+            return;
+        }
+        [scen_path, rest @ ..] if scen_path.ends_with(".scen") && is_maps_dir(rest) => {
+            run_scenarios(scen_path, rest.first());
+            return;
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(1);
+        }
+    };
+    let mut maps = Maps::new(first_seed);
+    println!("First seed: {}\n", first_seed);
+
     println!("Time benchmarks");
     println!(
         "{: <10} {: >12} {: >12} {: >12}",
@@ -10,25 +155,25 @@ fn main() {
     println!(
         "{: <10} {: >10}μs {: >10}μs {: >10}μs",
         "Manhattan",
-        get_average_duration(200, 200, 15, get_manhattan_dist, 5000),
-        get_average_duration(100, 100, 15, get_manhattan_dist, 5000),
-        get_average_duration(50, 50, 15, get_manhattan_dist, 5000),
+        get_average_duration(&mut maps, 200, 200, 15, MANHATTAN, A_STAR, 5000),
+        get_average_duration(&mut maps, 100, 100, 15, MANHATTAN, A_STAR, 5000),
+        get_average_duration(&mut maps, 50, 50, 15, MANHATTAN, A_STAR, 5000),
     );
     println!(
         "{: <10} {: >10}μs {: >10}μs {: >10}μs",
         "Euclidean",
-        get_average_duration(200, 200, 15, get_euclidean_dist, 5000),
-        get_average_duration(100, 100, 15, get_euclidean_dist, 5000),
-        get_average_duration(50, 50, 15, get_euclidean_dist, 5000),
+        get_average_duration(&mut maps, 200, 200, 15, EUCLIDEAN, A_STAR, 5000),
+        get_average_duration(&mut maps, 100, 100, 15, EUCLIDEAN, A_STAR, 5000),
+        get_average_duration(&mut maps, 50, 50, 15, EUCLIDEAN, A_STAR, 5000),
     );
     println!(
         "{: <10} {: >10}μs {: >10}μs {: >10}μs",
         "Chebyshev",
-        get_average_duration(200, 200, 15, get_chebyshev_dist, 5000),
-        get_average_duration(100, 100, 15, get_chebyshev_dist, 5000),
-        get_average_duration(50, 50, 15, get_chebyshev_dist, 5000),
+        get_average_duration(&mut maps, 200, 200, 15, CHEBYSHEV, A_STAR, 5000),
+        get_average_duration(&mut maps, 100, 100, 15, CHEBYSHEV, A_STAR, 5000),
+        get_average_duration(&mut maps, 50, 50, 15, CHEBYSHEV, A_STAR, 5000),
     );
-    println!("");
+    println!();
     println!(
         "{: <10} {: >12} {: >12} {: >12}",
         "25%", "200x200", "100x100", "50x50"
@@ -36,25 +181,25 @@ fn main() {
     println!(
         "{: <10} {: >10}μs {: >10}μs {: >10}μs",
         "Manhattan",
-        get_average_duration(200, 200, 25, get_manhattan_dist, 5000),
-        get_average_duration(100, 100, 25, get_manhattan_dist, 5000),
-        get_average_duration(50, 50, 25, get_manhattan_dist, 5000),
+        get_average_duration(&mut maps, 200, 200, 25, MANHATTAN, A_STAR, 5000),
+        get_average_duration(&mut maps, 100, 100, 25, MANHATTAN, A_STAR, 5000),
+        get_average_duration(&mut maps, 50, 50, 25, MANHATTAN, A_STAR, 5000),
     );
     println!(
         "{: <10} {: >10}μs {: >10}μs {: >10}μs",
         "Euclidean",
-        get_average_duration(200, 200, 25, get_euclidean_dist, 5000),
-        get_average_duration(100, 100, 25, get_euclidean_dist, 5000),
-        get_average_duration(50, 50, 25, get_euclidean_dist, 5000),
+        get_average_duration(&mut maps, 200, 200, 25, EUCLIDEAN, A_STAR, 5000),
+        get_average_duration(&mut maps, 100, 100, 25, EUCLIDEAN, A_STAR, 5000),
+        get_average_duration(&mut maps, 50, 50, 25, EUCLIDEAN, A_STAR, 5000),
     );
     println!(
         "{: <10} {: >10}μs {: >10}μs {: >10}μs",
         "Chebyshev",
-        get_average_duration(200, 200, 25, get_chebyshev_dist, 5000),
-        get_average_duration(100, 100, 25, get_chebyshev_dist, 5000),
-        get_average_duration(50, 50, 25, get_chebyshev_dist, 5000),
+        get_average_duration(&mut maps, 200, 200, 25, CHEBYSHEV, A_STAR, 5000),
+        get_average_duration(&mut maps, 100, 100, 25, CHEBYSHEV, A_STAR, 5000),
+        get_average_duration(&mut maps, 50, 50, 25, CHEBYSHEV, A_STAR, 5000),
     );
-    println!("");
+    println!();
     println!(
         "{: <10} {: >12} {: >12} {: >12}",
         "35%", "200x200", "100x100", "50x50"
@@ -62,23 +207,23 @@ fn main() {
     println!(
         "{: <10} {: >10}μs {: >10}μs {: >10}μs",
         "Manhattan",
-        get_average_duration(200, 200, 35, get_manhattan_dist, 500),
-        get_average_duration(100, 100, 35, get_manhattan_dist, 500),
-        get_average_duration(50, 50, 35, get_manhattan_dist, 500),
+        get_average_duration(&mut maps, 200, 200, 35, MANHATTAN, A_STAR, 500),
+        get_average_duration(&mut maps, 100, 100, 35, MANHATTAN, A_STAR, 500),
+        get_average_duration(&mut maps, 50, 50, 35, MANHATTAN, A_STAR, 500),
     );
     println!(
         "{: <10} {: >10}μs {: >10}μs {: >10}μs",
         "Euclidean",
-        get_average_duration(200, 200, 35, get_euclidean_dist, 500),
-        get_average_duration(100, 100, 35, get_euclidean_dist, 500),
-        get_average_duration(50, 50, 35, get_euclidean_dist, 500),
+        get_average_duration(&mut maps, 200, 200, 35, EUCLIDEAN, A_STAR, 500),
+        get_average_duration(&mut maps, 100, 100, 35, EUCLIDEAN, A_STAR, 500),
+        get_average_duration(&mut maps, 50, 50, 35, EUCLIDEAN, A_STAR, 500),
     );
     println!(
         "{: <10} {: >10}μs {: >10}μs {: >10}μs",
         "Chebyshev",
-        get_average_duration(200, 200, 35, get_chebyshev_dist, 500),
-        get_average_duration(100, 100, 35, get_chebyshev_dist, 500),
-        get_average_duration(50, 50, 35, get_chebyshev_dist, 500),
+        get_average_duration(&mut maps, 200, 200, 35, CHEBYSHEV, A_STAR, 500),
+        get_average_duration(&mut maps, 100, 100, 35, CHEBYSHEV, A_STAR, 500),
+        get_average_duration(&mut maps, 50, 50, 35, CHEBYSHEV, A_STAR, 500),
     );
 
     println!("\nNumber of explored nodes benchmarks");
@@ -89,25 +234,25 @@ fn main() {
     println!(
         "{: <10} {: >10} {: >10} {: >10}",
         "Manhattan",
-        get_average_explored(200, 200, 15, get_manhattan_dist, 5000),
-        get_average_explored(100, 100, 15, get_manhattan_dist, 5000),
-        get_average_explored(50, 50, 15, get_manhattan_dist, 5000),
+        get_average_explored(&mut maps, 200, 200, 15, MANHATTAN, A_STAR, 5000),
+        get_average_explored(&mut maps, 100, 100, 15, MANHATTAN, A_STAR, 5000),
+        get_average_explored(&mut maps, 50, 50, 15, MANHATTAN, A_STAR, 5000),
     );
     println!(
         "{: <10} {: >10} {: >10} {: >10}",
         "Euclidean",
-        get_average_explored(200, 200, 15, get_euclidean_dist, 5000),
-        get_average_explored(100, 100, 15, get_euclidean_dist, 5000),
-        get_average_explored(50, 50, 15, get_euclidean_dist, 5000),
+        get_average_explored(&mut maps, 200, 200, 15, EUCLIDEAN, A_STAR, 5000),
+        get_average_explored(&mut maps, 100, 100, 15, EUCLIDEAN, A_STAR, 5000),
+        get_average_explored(&mut maps, 50, 50, 15, EUCLIDEAN, A_STAR, 5000),
     );
     println!(
         "{: <10} {: >10} {: >10} {: >10}",
         "Chebyshev",
-        get_average_explored(200, 200, 15, get_chebyshev_dist, 5000),
-        get_average_explored(100, 100, 15, get_chebyshev_dist, 5000),
-        get_average_explored(50, 50, 15, get_chebyshev_dist, 5000),
+        get_average_explored(&mut maps, 200, 200, 15, CHEBYSHEV, A_STAR, 5000),
+        get_average_explored(&mut maps, 100, 100, 15, CHEBYSHEV, A_STAR, 5000),
+        get_average_explored(&mut maps, 50, 50, 15, CHEBYSHEV, A_STAR, 5000),
     );
-    println!("");
+    println!();
     println!(
         "{: <10} {: >12} {: >12} {: >12}",
         "25%", "200x200", "100x100", "50x50"
@@ -115,25 +260,25 @@ fn main() {
     println!(
         "{: <10} {: >10} {: >10} {: >10}",
         "Manhattan",
-        get_average_explored(200, 200, 25, get_manhattan_dist, 5000),
-        get_average_explored(100, 100, 25, get_manhattan_dist, 5000),
-        get_average_explored(50, 50, 25, get_manhattan_dist, 5000),
+        get_average_explored(&mut maps, 200, 200, 25, MANHATTAN, A_STAR, 5000),
+        get_average_explored(&mut maps, 100, 100, 25, MANHATTAN, A_STAR, 5000),
+        get_average_explored(&mut maps, 50, 50, 25, MANHATTAN, A_STAR, 5000),
     );
     println!(
         "{: <10} {: >10} {: >10} {: >10}",
         "Euclidean",
-        get_average_explored(200, 200, 25, get_euclidean_dist, 5000),
-        get_average_explored(100, 100, 25, get_euclidean_dist, 5000),
-        get_average_explored(50, 50, 25, get_euclidean_dist, 5000),
+        get_average_explored(&mut maps, 200, 200, 25, EUCLIDEAN, A_STAR, 5000),
+        get_average_explored(&mut maps, 100, 100, 25, EUCLIDEAN, A_STAR, 5000),
+        get_average_explored(&mut maps, 50, 50, 25, EUCLIDEAN, A_STAR, 5000),
     );
     println!(
         "{: <10} {: >10} {: >10} {: >10}",
         "Chebyshev",
-        get_average_explored(200, 200, 25, get_chebyshev_dist, 5000),
-        get_average_explored(100, 100, 25, get_chebyshev_dist, 5000),
-        get_average_explored(50, 50, 25, get_chebyshev_dist, 5000),
+        get_average_explored(&mut maps, 200, 200, 25, CHEBYSHEV, A_STAR, 5000),
+        get_average_explored(&mut maps, 100, 100, 25, CHEBYSHEV, A_STAR, 5000),
+        get_average_explored(&mut maps, 50, 50, 25, CHEBYSHEV, A_STAR, 5000),
     );
-    println!("");
+    println!();
     println!(
         "{: <10} {: >10} {: >10} {: >10}",
         "35%", "200x200", "100x100", "50x50"
@@ -141,83 +286,259 @@ fn main() {
     println!(
         "{: <10} {: >10} {: >10} {: >10}",
         "Manhattan",
-        get_average_explored(200, 200, 35, get_manhattan_dist, 500),
-        get_average_explored(100, 100, 35, get_manhattan_dist, 500),
-        get_average_explored(50, 50, 35, get_manhattan_dist, 500),
+        get_average_explored(&mut maps, 200, 200, 35, MANHATTAN, A_STAR, 500),
+        get_average_explored(&mut maps, 100, 100, 35, MANHATTAN, A_STAR, 500),
+        get_average_explored(&mut maps, 50, 50, 35, MANHATTAN, A_STAR, 500),
     );
     println!(
         "{: <10} {: >10} {: >10} {: >10}",
         "Euclidean",
-        get_average_explored(200, 200, 35, get_euclidean_dist, 500),
-        get_average_explored(100, 100, 35, get_euclidean_dist, 500),
-        get_average_explored(50, 50, 35, get_euclidean_dist, 500),
+        get_average_explored(&mut maps, 200, 200, 35, EUCLIDEAN, A_STAR, 500),
+        get_average_explored(&mut maps, 100, 100, 35, EUCLIDEAN, A_STAR, 500),
+        get_average_explored(&mut maps, 50, 50, 35, EUCLIDEAN, A_STAR, 500),
     );
     println!(
         "{: <10} {: >10} {: >10} {: >10}",
         "Chebyshev",
-        get_average_explored(200, 200, 35, get_chebyshev_dist, 500),
-        get_average_explored(100, 100, 35, get_chebyshev_dist, 500),
-        get_average_explored(50, 50, 35, get_chebyshev_dist, 500),
+        get_average_explored(&mut maps, 200, 200, 35, CHEBYSHEV, A_STAR, 500),
+        get_average_explored(&mut maps, 100, 100, 35, CHEBYSHEV, A_STAR, 500),
+        get_average_explored(&mut maps, 50, 50, 35, CHEBYSHEV, A_STAR, 500),
+    );
+
+    println!("\nSearch algorithms benchmarks");
+    for &wall_percentage in &[15, 25, 35] {
+        println!();
+        println!(
+            "{: <10} {: >12} {: >12} {: >12}",
+            format!("{}%", wall_percentage),
+            "200x200",
+            "100x100",
+            "50x50"
+        );
+        for &algorithm in &ALGORITHMS {
+            println!(
+                "{: <10} {: >10}μs {: >10}μs {: >10}μs",
+                format!("{} time", algorithm.0),
+                get_average_duration(
+                    &mut maps,
+                    200,
+                    200,
+                    wall_percentage,
+                    MANHATTAN,
+                    algorithm,
+                    500
+                ),
+                get_average_duration(
+                    &mut maps,
+                    100,
+                    100,
+                    wall_percentage,
+                    MANHATTAN,
+                    algorithm,
+                    500
+                ),
+                get_average_duration(
+                    &mut maps,
+                    50,
+                    50,
+                    wall_percentage,
+                    MANHATTAN,
+                    algorithm,
+                    500
+                ),
+            );
+            println!(
+                "{: <10} {: >12} {: >12} {: >12}",
+                format!("{} nodes", algorithm.0),
+                get_average_explored(
+                    &mut maps,
+                    200,
+                    200,
+                    wall_percentage,
+                    MANHATTAN,
+                    algorithm,
+                    500
+                ),
+                get_average_explored(
+                    &mut maps,
+                    100,
+                    100,
+                    wall_percentage,
+                    MANHATTAN,
+                    algorithm,
+                    500
+                ),
+                get_average_explored(
+                    &mut maps,
+                    50,
+                    50,
+                    wall_percentage,
+                    MANHATTAN,
+                    algorithm,
+                    500
+                ),
+            );
+        }
+    }
+
+    println!("\nMaps generated per map with a path");
+    println!("{: <10} {: >12} {: >12}", "Size", "Walls", "Maps");
+    for attempts in &maps.attempts {
+        println!(
+            "{: <10} {: >11}% {: >12.2}",
+            format!("{}x{}", attempts.size.0, attempts.size.1),
+            attempts.wall_percentage,
+            attempts.generated as f64 / attempts.solvable as f64
+        );
+    }
+
+    println!("\nSlowest maps");
+    println!(
+        "{: <10} {: >12} {: <10} {: <10} {: >12}  Replay with",
+        "Size", "Walls", "Algorithm", "Heuristic", "Time"
     );
+    for case in &maps.slowest {
+        println!(
+            "{: <10} {: >11}% {: <10} {: <10} {: >10}μs  benchmarks --replay {} {} {} {}",
+            format!("{}x{}", case.size.0, case.size.1),
+            case.wall_percentage,
+            case.algorithm,
+            case.heuristic,
+            case.micros,
+            case.seed,
+            case.size.0,
+            case.size.1,
+            case.wall_percentage
+        );
+    }
+}
+
+//the arguments after the .scen file, which can only be the directory of the maps
+fn is_maps_dir(args: &[String]) -> bool {
+    match args {
+        [] => true,
+        [dir] => !dir.starts_with('-'),
+        _ => false,
+    }
+}
+
+fn run_scenarios(scen_path: &str, maps_dir: Option<&String>) {
+    if let Err(err) = scenarios::run_scenarios(Path::new(scen_path), maps_dir.map(Path::new)) {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+}
+
+//generates one of the maps of a run again and searches it with every algorithm and heuristic
+fn replay(seed: u64, m: usize, n: usize, wall_percentage: usize) {
+    let mut grid = Grid::new(m, n);
+    if grid
+        .fill_random_solvable_with_seed(wall_percentage, seed)
+        .is_none()
+    {
+        eprintln!("Couldn't generate a map with a path");
+        std::process::exit(1);
+    }
+    let (car, goal) = (grid.get_car().unwrap(), grid.get_goal().unwrap());
+    println!(
+        "{}x{} map with {}% of walls and seed {}\n",
+        m, n, wall_percentage, seed
+    );
+    println!(
+        "{: <10} {: <10} {: >12} {: >12}",
+        "Algorithm", "Heuristic", "Time", "Explored"
+    );
+    for &(name, algorithm) in &ALGORITHMS {
+        for &(heuristic_name, heuristic) in &[MANHATTAN, EUCLIDEAN, CHEBYSHEV] {
+            let instant = Instant::now();
+            let result = algorithm(&grid, car, goal, heuristic);
+            let duration = instant.elapsed();
+            println!(
+                "{: <10} {: <10} {: >10}μs {: >12}",
+                name,
+                heuristic_name,
+                duration.as_micros(),
+                result.get_n_explored()
+            );
+        }
+    }
 }
 
 fn get_average_duration(
+    maps: &mut Maps,
     m: usize,
     n: usize,
     wall_percentage: usize,
-    heuristic: fn((usize, usize), (usize, usize)) -> f32,
+    (heuristic_name, heuristic): NamedHeuristic,
+    (algorithm_name, algorithm): NamedAlgorithm,
     repetitions: usize,
 ) -> u128 {
     let mut grid = Grid::new(m, n);
     let mut acc = 0;
     for _ in 0..repetitions {
-        acc += loop {
-            grid.fill_random(wall_percentage);
-            let instant = Instant::now();
-            let result = grid.find_path(heuristic);
-            let duration = instant.elapsed();
-            if let Some(_) = result {
-                break duration.as_micros();
-            }
-        }
+        let seed = maps.generate(&mut grid, wall_percentage);
+        let (car, goal) = (grid.get_car().unwrap(), grid.get_goal().unwrap());
+        let instant = Instant::now();
+        algorithm(&grid, car, goal, heuristic);
+        let duration = instant.elapsed();
+        maps.record(SlowCase {
+            micros: duration.as_micros(),
+            size: (m, n),
+            wall_percentage,
+            seed,
+            algorithm: algorithm_name,
+            heuristic: heuristic_name,
+        });
+        acc += duration.as_micros();
     }
     acc / repetitions as u128
 }
 
 fn get_average_explored(
+    maps: &mut Maps,
     m: usize,
     n: usize,
     wall_percentage: usize,
-    heuristic: fn((usize, usize), (usize, usize)) -> f32,
+    (_, heuristic): NamedHeuristic,
+    (_, algorithm): NamedAlgorithm,
     repetitions: usize,
 ) -> u128 {
     let mut grid = Grid::new(m, n);
     let mut acc = 0;
     for _ in 0..repetitions {
-        acc += loop {
-            grid.fill_random(wall_percentage);
-            let result = grid.find_path(heuristic);
-            if let Some(path_result) = result {
-                break path_result.get_n_explored();
-            }
-        } as u128
+        maps.generate(&mut grid, wall_percentage);
+        let (car, goal) = (grid.get_car().unwrap(), grid.get_goal().unwrap());
+        let path_result = algorithm(&grid, car, goal, heuristic)
+            .into_path()
+            .expect("The generated maps always have a path");
+        acc += path_result.get_n_explored() as u128;
     }
     acc / repetitions as u128
 }
 
-fn get_manhattan_dist(pos1: (usize, usize), pos2: (usize, usize)) -> f32 {
-    (max(pos1.0, pos2.0) - min(pos1.0, pos2.0) + max(pos1.1, pos2.1) - min(pos1.1, pos2.1)) as f32
+fn a_star(
+    grid: &Grid,
+    start: (usize, usize),
+    goal: (usize, usize),
+    heuristic: &dyn Heuristic,
+) -> SearchResult {
+    grid.search(start, goal, heuristic)
 }
 
-fn get_euclidean_dist(pos1: (usize, usize), pos2: (usize, usize)) -> f32 {
-    let pos1 = (pos1.0 as f32, pos1.1 as f32);
-    let pos2 = (pos2.0 as f32, pos2.1 as f32);
-    ((pos1.0 - pos2.0).powi(2) + (pos1.1 - pos2.1).powi(2)).sqrt()
+fn jump_point(
+    grid: &Grid,
+    start: (usize, usize),
+    goal: (usize, usize),
+    heuristic: &dyn Heuristic,
+) -> SearchResult {
+    grid.jump_point_search(start, goal, heuristic)
 }
 
-fn get_chebyshev_dist(pos1: (usize, usize), pos2: (usize, usize)) -> f32 {
-    max(
-        max(pos1.0, pos2.0) - min(pos1.0, pos2.0),
-        max(pos1.1, pos2.1) - min(pos1.1, pos2.1),
-    ) as f32
+fn bidirectional(
+    grid: &Grid,
+    start: (usize, usize),
+    goal: (usize, usize),
+    heuristic: &dyn Heuristic,
+) -> SearchResult {
+    grid.bidirectional_search(start, goal, heuristic)
 }
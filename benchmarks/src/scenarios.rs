@@ -0,0 +1,173 @@
+use super::{a_star, bidirectional, jump_point, Algorithm};
+use grid::heuristic::Octile;
+use grid::{Grid, PathResult};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+//the optimal lengths are written with a few decimals
+const LENGTH_TOLERANCE: f64 = 1e-4;
+
+//a line of a MovingAI .scen file:
+//bucket map width height start_x start_y goal_x goal_y optimal_length
+struct Scenario {
+    line: usize,
+    map: String,
+    size: (usize, usize),
+    start: (usize, usize),
+    goal: (usize, usize),
+    optimal_length: f64,
+}
+
+//runs every scenario of the file with every algorithm and checks the lengths of the paths found
+//the maps are searched relative to maps_dir, which is the directory of the file by default
+pub fn run_scenarios(scen_path: &Path, maps_dir: Option<&Path>) -> Result<(), String> {
+    let scen = fs::read_to_string(scen_path)
+        .map_err(|err| format!("Couldn't read {}: {}", scen_path.display(), err))?;
+    let scenarios = parse_scenarios(&scen)
+        .map_err(|err| format!("Couldn't load {}: {}", scen_path.display(), err))?;
+    let maps_dir = maps_dir
+        .map(Path::to_path_buf)
+        .or_else(|| scen_path.parent().map(Path::to_path_buf))
+        .unwrap_or_default();
+    let mut maps = HashMap::new();
+    for scenario in &scenarios {
+        if !maps.contains_key(&scenario.map) {
+            maps.insert(scenario.map.clone(), load_map(&maps_dir, &scenario.map)?);
+        }
+        let grid: &Grid = &maps[&scenario.map];
+        if (grid.n(), grid.m()) != scenario.size
+            || !grid.is_inside(scenario.start)
+            || !grid.is_inside(scenario.goal)
+        {
+            return Err(format!(
+                "The scenario in line {} doesn't fit in the map {}",
+                scenario.line, scenario.map
+            ));
+        }
+    }
+
+    println!("{} scenarios from {}", scenarios.len(), scen_path.display());
+    println!(
+        "{: <10} {: >12} {: >12} {: >12}",
+        "", "Wrong", "Time", "Nodes"
+    );
+    for &(name, algorithm) in &[
+        ("A*", a_star as Algorithm),
+        ("JPS", jump_point),
+        ("Bidir", bidirectional),
+    ] {
+        let mut wrong = Vec::new();
+        let mut time = Duration::default();
+        let mut explored = 0;
+        for scenario in &scenarios {
+            let grid = &maps[&scenario.map];
+            let instant = Instant::now();
+            let result = algorithm(grid, scenario.start, scenario.goal, &Octile).into_path();
+            time += instant.elapsed();
+            let length = result.as_ref().map(get_octile_length);
+            explored += result.as_ref().map_or(0, PathResult::get_n_explored);
+            match length {
+                Some(length)
+                    if (length - scenario.optimal_length).abs()
+                        <= LENGTH_TOLERANCE * scenario.optimal_length.max(1.0) => {}
+                _ => wrong.push((scenario, length)),
+            }
+        }
+        println!(
+            "{: <10} {: >12} {: >10}μs {: >12}",
+            name,
+            wrong.len(),
+            time.as_micros() / scenarios.len().max(1) as u128,
+            explored / scenarios.len().max(1),
+        );
+        for (scenario, length) in wrong {
+            match length {
+                Some(length) => println!(
+                    "    line {}: found a path with length {} but the optimal length is {}",
+                    scenario.line, length, scenario.optimal_length
+                ),
+                None => println!(
+                    "    line {}: no path found but the optimal length is {}",
+                    scenario.line, scenario.optimal_length
+                ),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_scenarios(scen: &str) -> Result<Vec<Scenario>, String> {
+    let mut lines = scen.lines().enumerate();
+    match lines.next() {
+        Some((_, header)) if header.trim().starts_with("version") => {}
+        _ => return Err(String::from("line 1: expected \"version <number>\"")),
+    }
+    let mut scenarios = Vec::new();
+    for (i, line) in lines {
+        let line_number = i + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 9 {
+            return Err(format!(
+                "line {}: expected 9 fields, found {}",
+                line_number,
+                fields.len()
+            ));
+        }
+        let parse_number = |index: usize| {
+            fields[index].parse::<usize>().map_err(|_| {
+                format!(
+                    "line {}: expected a positive integer, found \"{}\"",
+                    line_number, fields[index]
+                )
+            })
+        };
+        scenarios.push(Scenario {
+            line: line_number,
+            map: fields[1].to_string(),
+            size: (parse_number(2)?, parse_number(3)?),
+            start: (parse_number(4)?, parse_number(5)?),
+            goal: (parse_number(6)?, parse_number(7)?),
+            optimal_length: fields[8].parse().map_err(|_| {
+                format!(
+                    "line {}: expected a length, found \"{}\"",
+                    line_number, fields[8]
+                )
+            })?,
+        });
+    }
+    Ok(scenarios)
+}
+
+//the scenarios usually name the maps with the directory of their benchmark set
+//so if the map isn't there it's looked for directly in maps_dir
+fn load_map(maps_dir: &Path, map: &str) -> Result<Grid, String> {
+    let mut path = maps_dir.join(map);
+    if !path.exists() {
+        if let Some(file_name) = Path::new(map).file_name() {
+            path = maps_dir.join(file_name);
+        }
+    }
+    let text = fs::read_to_string(&path)
+        .map_err(|err| format!("Couldn't read the map {}: {}", path.display(), err))?;
+    Grid::from_moving_ai(&text)
+        .map_err(|err| format!("Couldn't load the map {}: {}", path.display(), err))
+}
+
+//the cost of the path is added in f32, which isn't precise enough for the longest paths
+fn get_octile_length(path: &PathResult) -> f64 {
+    path.get_directions()
+        .iter()
+        .map(|dir| {
+            if dir.is_diagonal() {
+                std::f64::consts::SQRT_2
+            } else {
+                1.0
+            }
+        })
+        .sum()
+}
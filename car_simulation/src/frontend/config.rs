@@ -0,0 +1,239 @@
+use super::keys::{get_key_name, parse_key, Action};
+use super::FrontEnd;
+use crossterm::style::Color;
+use grid::{Theme, MAX_WEIGHT};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const THEMES: [&str; 3] = ["default", "high-contrast", "color-blind"];
+
+//the file read when none is given, which doesn't need to exist
+fn get_default_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("car_simulation").join("config.toml"))
+}
+
+//an example of the file:
+//
+//theme = "color-blind"
+//
+//[colors]
+//wall = "dark_grey"
+//explored = "#e69f00"
+//weighted = [235, 237, 239, 241, 243, 245, 247, 249]
+//
+//[glyphs]
+//car = "[]"
+//trace = { left = "<-", right = "->" }
+//
+//[keys]
+//run = "Space"
+//place = "Enter"
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    theme: Option<String>,
+    colors: ColorsConfig,
+    glyphs: GlyphsConfig,
+    //the name of an action and its key
+    keys: BTreeMap<String, String>,
+}
+
+//the names of the crossterm colors, like dark_blue, an rgb color like #0072b2 or an ansi value
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ColorsConfig {
+    border: Option<ColorValue>,
+    wall: Option<ColorValue>,
+    empty: Option<ColorValue>,
+    //one for every cost from 2 to MAX_WEIGHT
+    weighted: Option<Vec<ColorValue>>,
+    explored: Option<ColorValue>,
+    explored_backward: Option<ColorValue>,
+    open: Option<ColorValue>,
+    current: Option<ColorValue>,
+    car: Option<ColorValue>,
+    goal: Option<ColorValue>,
+    trace: Option<ColorValue>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ColorValue {
+    Name(String),
+    Ansi(u8),
+}
+
+//the glyphs must be two columns wide
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct GlyphsConfig {
+    car: Option<String>,
+    goal: Option<String>,
+    trace: TraceGlyphsConfig,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct TraceGlyphsConfig {
+    left: Option<String>,
+    up: Option<String>,
+    right: Option<String>,
+    down: Option<String>,
+    up_left: Option<String>,
+    up_right: Option<String>,
+    down_left: Option<String>,
+    down_right: Option<String>,
+}
+
+impl FrontEnd {
+    //reads the colors, the glyphs and the keys from the file, or from the default one if it exists
+    //the theme given replaces the one of the file, but not the colors changed in it
+    pub fn load_config(&mut self, path: Option<&str>, theme: Option<&str>) -> Result<(), String> {
+        let path = match path {
+            Some(path) => Some(PathBuf::from(path)),
+            None => get_default_path().filter(|path| path.exists()),
+        };
+        let config = match path {
+            Some(path) => read_config(&path)?,
+            None => Config::default(),
+        };
+        let name = theme.or(config.theme.as_deref()).unwrap_or("default");
+        self.theme = get_theme(name).ok_or_else(|| {
+            format!(
+                "Unknown theme {}, it must be one of {}",
+                name,
+                THEMES.join(", ")
+            )
+        })?;
+        config.colors.apply(&mut self.theme)?;
+        config.glyphs.apply(&mut self.theme);
+        for (name, key) in &config.keys {
+            let action =
+                Action::from_name(name).ok_or_else(|| format!("Unknown action {}", name))?;
+            let key = parse_key(key).ok_or_else(|| format!("Unknown key {} for {}", key, name))?;
+            self.keys.retain(|&(_, bound)| bound != action);
+            self.keys.push((key, action));
+        }
+        //the keys are checked at the end, so two actions can swap their keys
+        //the ones used while playing only can't repeat among themselves
+        for (i, &(key, action)) in self.keys.iter().enumerate() {
+            let conflict = self.keys[i + 1..].iter().find(|&&(other_key, other)| {
+                other_key == key && other.is_playback() == action.is_playback()
+            });
+            if let Some(&(_, other)) = conflict {
+                return Err(format!(
+                    "The key {} is used by {} and {}",
+                    get_key_name(key),
+                    action.get_name(),
+                    other.get_name()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn read_config(path: &Path) -> Result<Config, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|err| format!("Couldn't read {}: {}", path.display(), err))?;
+    toml::from_str(&text).map_err(|err| format!("Invalid config file {}: {}", path.display(), err))
+}
+
+fn get_theme(name: &str) -> Option<Theme> {
+    match name {
+        "default" => Some(Theme::default()),
+        "high-contrast" => Some(Theme::high_contrast()),
+        "color-blind" => Some(Theme::color_blind_safe()),
+        _ => None,
+    }
+}
+
+impl ColorsConfig {
+    fn apply(&self, theme: &mut Theme) -> Result<(), String> {
+        let colors = [
+            (&self.border, &mut theme.border, "border"),
+            (&self.wall, &mut theme.wall, "wall"),
+            (&self.empty, &mut theme.empty, "empty"),
+            (&self.explored, &mut theme.explored, "explored"),
+            (
+                &self.explored_backward,
+                &mut theme.explored_backward,
+                "explored_backward",
+            ),
+            (&self.open, &mut theme.open, "open"),
+            (&self.current, &mut theme.current, "current"),
+            (&self.car, &mut theme.car, "car"),
+            (&self.goal, &mut theme.goal, "goal"),
+            (&self.trace, &mut theme.trace, "trace"),
+        ];
+        for (value, color, name) in colors {
+            if let Some(value) = value {
+                *color = value.parse(name)?;
+            }
+        }
+        if let Some(weighted) = &self.weighted {
+            if weighted.len() != theme.weighted.len() {
+                return Err(format!(
+                    "There must be {} weighted colors, one for every cost from 2 to {}",
+                    theme.weighted.len(),
+                    MAX_WEIGHT
+                ));
+            }
+            for (value, color) in weighted.iter().zip(theme.weighted.iter_mut()) {
+                *color = value.parse("weighted")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ColorValue {
+    fn parse(&self, name: &str) -> Result<Color, String> {
+        let text = match self {
+            ColorValue::Ansi(value) => return Ok(Color::AnsiValue(*value)),
+            ColorValue::Name(text) => text,
+        };
+        let hex = text.strip_prefix('#').filter(|hex| hex.len() == 6);
+        let color = match hex {
+            Some(hex) => match u32::from_str_radix(hex, 16) {
+                Ok(rgb) => Ok(Color::Rgb {
+                    r: (rgb >> 16) as u8,
+                    g: (rgb >> 8) as u8,
+                    b: rgb as u8,
+                }),
+                Err(_) => Err(()),
+            },
+            None => Color::try_from(text.as_str()),
+        };
+        color.map_err(|_| format!("Unknown color {} for {}", text, name))
+    }
+}
+
+impl GlyphsConfig {
+    fn apply(&self, theme: &mut Theme) {
+        if let Some(car) = &self.car {
+            theme.car_glyph = car.clone();
+        }
+        if let Some(goal) = &self.goal {
+            theme.goal_glyph = goal.clone();
+        }
+        let trace = [
+            &self.trace.left,
+            &self.trace.up,
+            &self.trace.right,
+            &self.trace.down,
+            &self.trace.up_left,
+            &self.trace.up_right,
+            &self.trace.down_left,
+            &self.trace.down_right,
+        ];
+        for (glyph, theme_glyph) in trace.iter().zip(theme.trace_glyphs.iter_mut()) {
+            if let Some(glyph) = glyph {
+                *theme_glyph = glyph.clone();
+            }
+        }
+    }
+}
@@ -1,141 +1,251 @@
-use super::{FrontEnd, Heuristic, State};
+use super::keys::Action;
+use super::{Algorithm, FrontEnd, Generator, Heuristic, State};
 use crossterm::{
     cursor::Show,
-    event::{poll, read, DisableMouseCapture, Event, MouseButton, MouseEvent},
+    event::{poll, read, DisableMouseCapture, Event, KeyCode, KeyEvent, MouseButton, MouseEvent},
     execute,
-    terminal::{disable_raw_mode, size, LeaveAlternateScreen},
+    terminal::{disable_raw_mode, EnableLineWrap, LeaveAlternateScreen},
     Result,
 };
+use grid::{MazeAlgorithm, Movement, MAX_WEIGHT};
 use std::cmp::{max, min};
+use std::fs;
 use std::io::{stdout, Write};
 use std::time::Duration;
 
-const ROWS_MINUS_BUTTON_BEGIN: u16 = 0;
-const ROWS_MINUS_BUTTON_END: u16 = 1;
-const ROWS_PLUS_BUTTON_BEGIN: u16 = 6;
-const ROWS_PLUS_BUTTON_END: u16 = 7;
-const COLUMNS_MINUS_BUTTON_BEGIN: u16 = 9;
-const COLUMNS_MINUS_BUTTON_END: u16 = 10;
-const COLUMNS_PLUS_BUTTON_BEGIN: u16 = 18;
-const COLUMNS_PLUS_BUTTON_END: u16 = 19;
-const CAR_BUTTON_BEGIN: u16 = 21;
-const CAR_BUTTON_END: u16 = 23;
-const GOAL_BUTTON_BEGIN: u16 = 25;
-const GOAL_BUTTON_END: u16 = 28;
-const WALL_BUTTON_BEGIN: u16 = 30;
-const WALL_BUTTON_END: u16 = 33;
-const REMOVE_BUTTON_BEGIN: u16 = 35;
-const REMOVE_BUTTON_END: u16 = 40;
-const CLEAR_BUTTON_BEGIN: u16 = 42;
-const CLEAR_BUTTON_END: u16 = 46;
-const RANDOM_BUTTON_BEGIN: u16 = 48;
-const RANDOM_BUTTON_END: u16 = 53;
-const HEURISTIC_BUTTON_BEGIN: u16 = 55;
-const HEURISTIC_BUTTON_END: u16 = 63;
-const RUN_BUTTON_BEGIN: u16 = 65;
-const RUN_BUTTON_END: u16 = 67;
-const QUIT_BUTTON_BEGIN: u16 = 69;
-const QUIT_BUTTON_END: u16 = 72;
-
 impl FrontEnd {
     pub(super) fn process_event(&mut self) -> Result<()> {
-        let term_size = size()?;
-        let buttons_y = term_size.1 - 2;
-        match read()? {
+        let event = self.read_event()?;
+        let area = self.get_grid_area();
+        self.viewport.clamp(&self.grid, area);
+        self.cursor = (
+            min(self.cursor.0, self.grid.n() - 1),
+            min(self.cursor.1, self.grid.m() - 1),
+        );
+        //any key closes the help
+        if self.show_help {
+            if let Event::Key(_) = event {
+                self.show_help = false;
+            }
+            return Ok(());
+        }
+        match event {
             Event::Mouse(MouseEvent::Down(MouseButton::Left, x, y, ..)) => {
                 self.status_msg.clear();
-                if self.is_inside_grid((x as usize, y as usize)) {
-                    self.set_cell((x as usize, y as usize));
-                } else if y == buttons_y {
-                    self.process_clicked_button(x, term_size)?;
+                if let Some(cell) =
+                    self.viewport
+                        .get_cell(&self.grid, area, (x as usize, y as usize))
+                {
+                    self.cursor = cell;
+                    self.set_cell(cell);
+                } else if let Some(button) = self
+                    .get_buttons()
+                    .into_iter()
+                    .find(|button| button.row == y && button.columns.contains(&x))
+                {
+                    self.process_clicked_button(button.action)?;
                 }
             }
             Event::Mouse(MouseEvent::Drag(MouseButton::Left, x, y, ..)) => {
-                if self.is_inside_grid((x as usize, y as usize)) {
-                    self.set_cell((x as usize, y as usize));
+                if let Some(cell) =
+                    self.viewport
+                        .get_cell(&self.grid, area, (x as usize, y as usize))
+                {
+                    self.set_cell(cell);
                 }
             }
+            //the view is dragged with the right button
+            Event::Mouse(MouseEvent::Down(MouseButton::Right, x, y, ..)) => {
+                self.drag_start = Some((x, y));
+            }
+            Event::Mouse(MouseEvent::Drag(MouseButton::Right, x, y, ..)) => {
+                self.drag_view((x, y), area);
+            }
+            Event::Mouse(MouseEvent::Up(MouseButton::Right, ..)) => self.drag_start = None,
+            Event::Mouse(MouseEvent::ScrollUp(..)) => self.viewport.zoom_in(&self.grid, area),
+            Event::Mouse(MouseEvent::ScrollDown(..)) => self.viewport.zoom_out(&self.grid, area),
+            Event::Key(KeyEvent { code, .. }) => {
+                self.status_msg.clear();
+                self.process_key(code, area)?;
+            }
             _ => {}
         }
         Ok(())
     }
 
-    //keep in mind that cell are two spaces wide
-    fn is_inside_grid(&self, pos: (usize, usize)) -> bool {
-        pos.0 <= self.grid.n() * 2 + 1 && pos.0 >= 2 && pos.1 <= self.grid.m() && pos.1 >= 1
+    //every event is read with this, so the layout follows the size of the terminal
+    //even while a search or the car are being animated
+    pub(super) fn read_event(&mut self) -> Result<Event> {
+        let event = read()?;
+        if let Event::Resize(columns, rows) = event {
+            self.term_size = (columns, rows);
+            self.viewport.clamp(&self.grid, self.get_grid_area());
+        }
+        Ok(event)
+    }
+
+    //the arrows move the cursor and the view follows it, the rest of the keys are the ones
+    //bound to an action
+    fn process_key(&mut self, code: KeyCode, area: (usize, usize)) -> Result<()> {
+        let step = self.viewport.get_scale();
+        let (x, y) = self.cursor;
+        match code {
+            KeyCode::Left => self.move_cursor((x.saturating_sub(step), y), area),
+            KeyCode::Right => self.move_cursor((x + step, y), area),
+            KeyCode::Up => self.move_cursor((x, y.saturating_sub(step)), area),
+            KeyCode::Down => self.move_cursor((x, y + step), area),
+            code => {
+                if let Some(action) = self.get_action(code, false) {
+                    self.do_action(action)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn move_cursor(&mut self, pos: (usize, usize), area: (usize, usize)) {
+        self.cursor = (min(pos.0, self.grid.n() - 1), min(pos.1, self.grid.m() - 1));
+        self.viewport.scroll_to(&self.grid, area, self.cursor);
+    }
+
+    //the grid follows the mouse, so the view moves the other way
+    //without zoom the cells are two characters wide, so odd moves are kept for the next drag
+    fn drag_view(&mut self, pos: (u16, u16), area: (usize, usize)) {
+        let start = match self.drag_start {
+            Some(start) => start,
+            None => return,
+        };
+        let delta = (
+            pos.0 as isize - start.0 as isize,
+            pos.1 as isize - start.1 as isize,
+        );
+        let scale = self.viewport.get_scale() as isize;
+        let (dx, dy, used_columns) = if self.viewport.zoom_out == 0 {
+            (delta.0 / 2, delta.1, delta.0 / 2 * 2)
+        } else {
+            (delta.0 * scale, delta.1 * 2 * scale, delta.0)
+        };
+        self.viewport.pan(&self.grid, area, -dx, -dy);
+        self.drag_start = Some(((start.0 as isize + used_columns) as u16, pos.1));
     }
 
     fn set_cell(&mut self, pos: (usize, usize)) {
+        self.path.clear();
+        self.changed_cells.push(pos);
         match self.state {
-            State::Car => self
-                .grid
-                .set_car(pos.0 as usize / 2 - 1, pos.1 as usize - 1),
-            State::Goal => self
-                .grid
-                .set_goal(pos.0 as usize / 2 - 1, pos.1 as usize - 1),
-            State::Wall => self
-                .grid
-                .set_wall(pos.0 as usize / 2 - 1, pos.1 as usize - 1),
-            State::Remove => self
-                .grid
-                .set_empty(pos.0 as usize / 2 - 1, pos.1 as usize - 1),
+            State::Car => {
+                //the cell the car leaves gets its cost back, so the planner is told about it too
+                if let Some(car) = self.grid.get_car() {
+                    self.changed_cells.push(car);
+                }
+                self.grid.set_car(pos.0, pos.1);
+            }
+            State::Goal => self.grid.set_goal(pos.0, pos.1),
+            State::Wall => self.grid.set_wall(pos.0, pos.1),
+            State::Weighted => self.grid.set_weighted(pos.0, pos.1, self.weight),
+            State::Remove => self.grid.set_empty(pos.0, pos.1),
         }
     }
 
-    fn process_clicked_button(&mut self, x: u16, term_size: (u16, u16)) -> Result<()> {
-        if x >= ROWS_MINUS_BUTTON_BEGIN && x <= ROWS_MINUS_BUTTON_END {
-            self.change_height_while_clicked(-1, term_size.1 as usize)?;
-        } else if x >= ROWS_PLUS_BUTTON_BEGIN && x <= ROWS_PLUS_BUTTON_END {
-            self.change_height_while_clicked(1, term_size.1 as usize)?;
-        } else if x >= COLUMNS_MINUS_BUTTON_BEGIN && x <= COLUMNS_MINUS_BUTTON_END {
-            self.change_width_while_clicked(-1, term_size.0 as usize)?;
-        } else if x >= COLUMNS_PLUS_BUTTON_BEGIN && x <= COLUMNS_PLUS_BUTTON_END {
-            self.change_width_while_clicked(1, term_size.0 as usize)?;
-        } else if x >= CAR_BUTTON_BEGIN && x <= CAR_BUTTON_END {
-            self.state = State::Car;
-        } else if x >= GOAL_BUTTON_BEGIN && x <= GOAL_BUTTON_END {
-            self.state = State::Goal;
-        } else if x >= WALL_BUTTON_BEGIN && x <= WALL_BUTTON_END {
-            self.state = State::Wall;
-        } else if x >= REMOVE_BUTTON_BEGIN && x <= REMOVE_BUTTON_END {
-            self.state = State::Remove;
-        } else if x >= CLEAR_BUTTON_BEGIN && x <= CLEAR_BUTTON_END {
-            self.grid.clear();
-        } else if x >= RANDOM_BUTTON_BEGIN && x <= RANDOM_BUTTON_END {
-            self.grid.fill_random(self.wall_percentage);
-        } else if x >= HEURISTIC_BUTTON_BEGIN && x <= HEURISTIC_BUTTON_END {
-            self.change_heuristic();
-        } else if x >= RUN_BUTTON_BEGIN && x <= RUN_BUTTON_END {
-            self.run_simulation();
-        } else if x >= QUIT_BUTTON_BEGIN && x <= QUIT_BUTTON_END {
-            quit();
+    //the buttons that change the size of the grid keep changing it while they're clicked
+    fn process_clicked_button(&mut self, action: Action) -> Result<()> {
+        match action {
+            Action::RemoveRow => self.change_size_while_clicked(-1, 0)?,
+            Action::AddRow => self.change_size_while_clicked(1, 0)?,
+            Action::RemoveColumn => self.change_size_while_clicked(0, -1)?,
+            Action::AddColumn => self.change_size_while_clicked(0, 1)?,
+            action => self.do_action(action)?,
         }
         Ok(())
     }
 
-    fn change_height_while_clicked(&mut self, change: isize, term_height: usize) -> Result<()> {
-        loop {
-            let desired_height = self.grid.m() as isize + change;
-            self.grid
-                .set_height(min(max(desired_height, 1) as usize, term_height - 4));
-            self.draw_screen()?;
-            if poll(Duration::from_millis(50))? {
-                if let Event::Mouse(MouseEvent::Up(MouseButton::Left, ..)) = read()? {
-                    break;
-                }
+    fn do_action(&mut self, action: Action) -> Result<()> {
+        match action {
+            Action::RemoveRow => self.change_size(-1, 0),
+            Action::AddRow => self.change_size(1, 0),
+            Action::RemoveColumn => self.change_size(0, -1),
+            Action::AddColumn => self.change_size(0, 1),
+            Action::Car => self.state = State::Car,
+            Action::Goal => self.state = State::Goal,
+            Action::Wall => self.state = State::Wall,
+            Action::Cost => self.change_weight(),
+            Action::Remove => self.state = State::Remove,
+            Action::Clear => {
+                self.grid.clear();
+                self.path.clear();
+                self.map_seed = None;
+                self.reset_planner();
+            }
+            Action::Random => {
+                self.fill_random();
+                self.path.clear();
+                self.reset_planner();
+            }
+            Action::Generator => self.change_generator(),
+            Action::Algorithm => self.change_algorithm(),
+            Action::Heuristic => {
+                self.change_heuristic();
+                self.reset_planner();
             }
+            Action::Movement => {
+                self.change_movement();
+                self.path.clear();
+                self.reset_planner();
+            }
+            Action::Run => self.run_simulation()?,
+            Action::Drive => self.drive()?,
+            Action::Agent => self.run_agent()?,
+            Action::Save => self.save_map(),
+            Action::Quit => quit(),
+            Action::Place => self.set_cell(self.cursor),
+            Action::ZoomIn => self.viewport.zoom_in(&self.grid, self.get_grid_area()),
+            Action::ZoomOut => self.viewport.zoom_out(&self.grid, self.get_grid_area()),
+            Action::Help => self.show_help = true,
+            //they're only used while something is played
+            Action::Pause
+            | Action::Step
+            | Action::Restart
+            | Action::SwitchMap
+            | Action::Faster
+            | Action::Slower
+            | Action::Stop => {}
         }
         Ok(())
     }
 
-    fn change_width_while_clicked(&mut self, change: isize, term_width: usize) -> Result<()> {
+    fn fill_random(&mut self) {
+        match self.generator {
+            Generator::Walls => self
+                .grid
+                .fill_random_with_seed(self.wall_percentage, self.seed),
+            Generator::Maze(algorithm) => self
+                .grid
+                .fill_maze_with_seed(algorithm, self.braid, self.seed),
+            Generator::Cave => self.grid.fill_cave_with_seed(&self.cave_rules, self.seed),
+            Generator::Rooms => self.grid.fill_rooms_with_seed(&self.room_rules, self.seed),
+        }
+        self.map_seed = Some(self.seed);
+        self.seed = self.seed.wrapping_add(1);
+    }
+
+    fn save_map(&mut self) {
+        let map = if crate::is_moving_ai_map(&self.output_path) {
+            self.grid.to_moving_ai_string()
+        } else {
+            self.grid.to_map_string()
+        };
+        self.status_msg = match fs::write(&self.output_path, map) {
+            Ok(()) => format!("Map saved to {}", self.output_path),
+            Err(err) => format!("Couldn't save the map to {}: {}", self.output_path, err),
+        };
+    }
+
+    fn change_size_while_clicked(&mut self, rows: isize, columns: isize) -> Result<()> {
         loop {
-            let desired_width = self.grid.n() as isize + change;
-            self.grid
-                .set_width(min(max(desired_width, 1) as usize, term_width / 2 - 2));
+            self.change_size(rows, columns);
             self.draw_screen()?;
             if poll(Duration::from_millis(50))? {
-                if let Event::Mouse(MouseEvent::Up(MouseButton::Left, ..)) = read()? {
+                if let Event::Mouse(MouseEvent::Up(MouseButton::Left, ..)) = self.read_event()? {
                     break;
                 }
             }
@@ -143,17 +253,82 @@ impl FrontEnd {
         Ok(())
     }
 
+    //the grid always keeps at least a row and a column
+    fn change_size(&mut self, rows: isize, columns: isize) {
+        self.reset_planner();
+        self.path.clear();
+        let height = self.grid.m() as isize + rows;
+        let width = self.grid.n() as isize + columns;
+        self.grid.set_height(max(height, 1) as usize);
+        self.grid.set_width(max(width, 1) as usize);
+    }
+
+    //the first click selects the tool and the next ones change the cost of the cells
+    fn change_weight(&mut self) {
+        if self.state == State::Weighted {
+            self.weight = if self.weight == MAX_WEIGHT {
+                2
+            } else {
+                self.weight + 1
+            };
+        } else {
+            self.state = State::Weighted;
+        }
+    }
+
+    fn change_generator(&mut self) {
+        self.generator = match self.generator {
+            Generator::Walls => Generator::Maze(MazeAlgorithm::RecursiveBacktracker),
+            Generator::Maze(MazeAlgorithm::RecursiveBacktracker) => {
+                Generator::Maze(MazeAlgorithm::Prim)
+            }
+            Generator::Maze(MazeAlgorithm::Prim) => Generator::Maze(MazeAlgorithm::Kruskal),
+            Generator::Maze(MazeAlgorithm::Kruskal) => {
+                Generator::Maze(MazeAlgorithm::RecursiveDivision)
+            }
+            Generator::Maze(MazeAlgorithm::RecursiveDivision) => Generator::Cave,
+            Generator::Cave => Generator::Rooms,
+            Generator::Rooms => Generator::Walls,
+        }
+    }
+
+    fn change_algorithm(&mut self) {
+        self.algorithm = match self.algorithm {
+            Algorithm::AStar => Algorithm::JumpPoint,
+            Algorithm::JumpPoint => Algorithm::Bidirectional,
+            Algorithm::Bidirectional => Algorithm::DStarLite,
+            Algorithm::DStarLite => Algorithm::AStar,
+        }
+    }
+
     fn change_heuristic(&mut self) {
         self.heuristic = match self.heuristic {
             Heuristic::Manhattan => Heuristic::Euclidean,
             Heuristic::Euclidean => Heuristic::Chebyshev,
-            Heuristic::Chebyshev => Heuristic::Manhattan,
+            Heuristic::Chebyshev => Heuristic::Octile,
+            Heuristic::Octile => Heuristic::Dijkstra,
+            Heuristic::Dijkstra => Heuristic::Manhattan,
         }
     }
+
+    fn change_movement(&mut self) {
+        self.grid.set_movement(match self.grid.get_movement() {
+            Movement::FourConnected => Movement::EightConnected,
+            Movement::EightConnected => Movement::EightConnectedNoCornerCutting,
+            Movement::EightConnectedNoCornerCutting => Movement::FourConnected,
+        });
+    }
 }
 
 fn quit() -> ! {
     disable_raw_mode().unwrap();
-    execute!(stdout(), LeaveAlternateScreen, DisableMouseCapture, Show).unwrap();
+    execute!(
+        stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        EnableLineWrap,
+        Show
+    )
+    .unwrap();
     std::process::exit(0);
 }
@@ -0,0 +1,253 @@
+use crossterm::event::KeyCode;
+
+//what the buttons and the keys do
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(super) enum Action {
+    RemoveRow,
+    AddRow,
+    RemoveColumn,
+    AddColumn,
+    Car,
+    Goal,
+    Wall,
+    Cost,
+    Remove,
+    Clear,
+    Random,
+    Generator,
+    Algorithm,
+    Heuristic,
+    Movement,
+    Run,
+    Drive,
+    Agent,
+    Save,
+    Quit,
+    Place,
+    ZoomIn,
+    ZoomOut,
+    Help,
+    //the ones of the searches, the car and the agent while they're played
+    Pause,
+    Step,
+    Restart,
+    SwitchMap,
+    Faster,
+    Slower,
+    Stop,
+}
+
+impl Action {
+    //in the order they're listed in the help
+    pub(super) const ALL: [Action; 31] = [
+        Action::Place,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::RemoveRow,
+        Action::AddRow,
+        Action::RemoveColumn,
+        Action::AddColumn,
+        Action::Car,
+        Action::Goal,
+        Action::Wall,
+        Action::Cost,
+        Action::Remove,
+        Action::Clear,
+        Action::Random,
+        Action::Generator,
+        Action::Algorithm,
+        Action::Heuristic,
+        Action::Movement,
+        Action::Run,
+        Action::Drive,
+        Action::Agent,
+        Action::Save,
+        Action::Quit,
+        Action::Help,
+        Action::Pause,
+        Action::Step,
+        Action::Restart,
+        Action::SwitchMap,
+        Action::Faster,
+        Action::Slower,
+        Action::Stop,
+    ];
+
+    //the name used in the keys table of the config file
+    pub(super) fn get_name(&self) -> &'static str {
+        match self {
+            Action::RemoveRow => "remove_row",
+            Action::AddRow => "add_row",
+            Action::RemoveColumn => "remove_column",
+            Action::AddColumn => "add_column",
+            Action::Car => "car",
+            Action::Goal => "goal",
+            Action::Wall => "wall",
+            Action::Cost => "cost",
+            Action::Remove => "remove",
+            Action::Clear => "clear",
+            Action::Random => "random",
+            Action::Generator => "generator",
+            Action::Algorithm => "algorithm",
+            Action::Heuristic => "heuristic",
+            Action::Movement => "movement",
+            Action::Run => "run",
+            Action::Drive => "drive",
+            Action::Agent => "agent",
+            Action::Save => "save",
+            Action::Quit => "quit",
+            Action::Place => "place",
+            Action::ZoomIn => "zoom_in",
+            Action::ZoomOut => "zoom_out",
+            Action::Help => "help",
+            Action::Pause => "pause",
+            Action::Step => "step",
+            Action::Restart => "restart",
+            Action::SwitchMap => "switch_map",
+            Action::Faster => "faster",
+            Action::Slower => "slower",
+            Action::Stop => "stop",
+        }
+    }
+
+    pub(super) fn get_default_key(&self) -> KeyCode {
+        match self {
+            Action::RemoveRow => KeyCode::Char('m'),
+            Action::AddRow => KeyCode::Char('M'),
+            Action::RemoveColumn => KeyCode::Char('n'),
+            Action::AddColumn => KeyCode::Char('N'),
+            Action::Car => KeyCode::Char('c'),
+            Action::Goal => KeyCode::Char('g'),
+            Action::Wall => KeyCode::Char('w'),
+            Action::Cost => KeyCode::Char('k'),
+            Action::Remove => KeyCode::Char('e'),
+            Action::Clear => KeyCode::Char('x'),
+            Action::Random => KeyCode::Char('r'),
+            Action::Generator => KeyCode::Char('t'),
+            Action::Algorithm => KeyCode::Char('a'),
+            Action::Heuristic => KeyCode::Char('h'),
+            Action::Movement => KeyCode::Char('v'),
+            Action::Run => KeyCode::Enter,
+            Action::Drive => KeyCode::Char('d'),
+            Action::Agent => KeyCode::Char('i'),
+            Action::Save => KeyCode::Char('s'),
+            Action::Quit => KeyCode::Char('q'),
+            Action::Place => KeyCode::Char(' '),
+            Action::ZoomIn => KeyCode::Char('+'),
+            Action::ZoomOut => KeyCode::Char('-'),
+            Action::Help => KeyCode::Char('?'),
+            Action::Pause => KeyCode::Char(' '),
+            Action::Step => KeyCode::Char('n'),
+            Action::Restart => KeyCode::Char('r'),
+            Action::SwitchMap => KeyCode::Char('b'),
+            Action::Faster => KeyCode::Char('+'),
+            Action::Slower => KeyCode::Char('-'),
+            Action::Stop => KeyCode::Esc,
+        }
+    }
+
+    pub(super) fn get_description(&self) -> &'static str {
+        match self {
+            Action::RemoveRow => "Removes a row",
+            Action::AddRow => "Adds a row",
+            Action::RemoveColumn => "Removes a column",
+            Action::AddColumn => "Adds a column",
+            Action::Car => "Places the car",
+            Action::Goal => "Places the goal",
+            Action::Wall => "Places walls",
+            Action::Cost => "Places cells with a cost, again to change it",
+            Action::Remove => "Empties cells",
+            Action::Clear => "Clears the grid",
+            Action::Random => "Generates a random map",
+            Action::Generator => "Changes the kind of random map",
+            Action::Algorithm => "Changes the search algorithm",
+            Action::Heuristic => "Changes the heuristic",
+            Action::Movement => "Changes how the car moves",
+            Action::Run => "Searches a path",
+            Action::Drive => "Drives the car along the path",
+            Action::Agent => "Runs a car that only knows what its sensors find",
+            Action::Save => "Saves the map",
+            Action::Quit => "Quits",
+            Action::Place => "Uses the tool in the cursor",
+            Action::ZoomIn => "Zooms in, also with the mouse wheel",
+            Action::ZoomOut => "Zooms out, also with the mouse wheel",
+            Action::Help => "Shows this help, any key closes it",
+            Action::Pause => "While playing, pauses and resumes",
+            Action::Step => "While paused, advances a step",
+            Action::Restart => "While driving, takes the car back to the start",
+            Action::SwitchMap => "While the agent runs, shows the real map or its own",
+            Action::Faster => "While playing, goes faster",
+            Action::Slower => "While playing, goes slower",
+            Action::Stop => "While playing, stops or skips to the end",
+        }
+    }
+
+    //the actions used while something is played, which can have the same keys as the others
+    pub(super) fn is_playback(&self) -> bool {
+        matches!(
+            self,
+            Action::Pause
+                | Action::Step
+                | Action::Restart
+                | Action::SwitchMap
+                | Action::Faster
+                | Action::Slower
+                | Action::Stop
+        )
+    }
+
+    pub(super) fn from_name(name: &str) -> Option<Action> {
+        Action::ALL
+            .iter()
+            .copied()
+            .find(|action| action.get_name() == name)
+    }
+}
+
+pub(super) fn get_default_keys() -> Vec<(KeyCode, Action)> {
+    Action::ALL
+        .iter()
+        .map(|action| (action.get_default_key(), *action))
+        .collect()
+}
+
+//a character or one of the names given by get_key_name
+pub(super) fn parse_key(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => return Some(KeyCode::Char(c)),
+        (None, _) => return None,
+        _ => {}
+    }
+    match name.to_lowercase().as_str() {
+        "enter" => Some(KeyCode::Enter),
+        "space" => Some(KeyCode::Char(' ')),
+        "tab" => Some(KeyCode::Tab),
+        "backspace" => Some(KeyCode::Backspace),
+        "esc" => Some(KeyCode::Esc),
+        _ => None,
+    }
+}
+
+pub(super) fn get_key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => String::from("Space"),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Enter => String::from("Enter"),
+        KeyCode::Tab => String::from("Tab"),
+        KeyCode::Backspace => String::from("Backspace"),
+        KeyCode::Esc => String::from("Esc"),
+        _ => String::new(),
+    }
+}
+
+//a single column, so it fits below the buttons
+pub(super) fn get_key_symbol(key: KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => String::from("␣"),
+        KeyCode::Enter => String::from("↵"),
+        KeyCode::Tab => String::from("⇥"),
+        KeyCode::Backspace => String::from("⌫"),
+        key => get_key_name(key),
+    }
+}
@@ -1,38 +1,112 @@
+use crossterm::event::KeyCode;
 use crossterm::{
     cursor::Hide,
     event::EnableMouseCapture,
     execute,
-    terminal::{enable_raw_mode, EnterAlternateScreen},
+    terminal::{enable_raw_mode, size, DisableLineWrap, EnterAlternateScreen},
     Result,
 };
-use grid::Grid;
+use grid::{heuristic, CaveRules, DStarLite, Grid, MazeAlgorithm, RoomRules, Theme, Viewport};
+use keys::{get_key_name, Action};
+use rand::{thread_rng, Rng};
 use std::io::{stdout, Write};
+use std::time::Duration;
+mod config;
+pub use config::THEMES;
 mod events;
+mod keys;
 mod run_simulation;
 mod screen;
 
+pub const DEFAULT_OUTPUT_PATH: &str = "saved_map";
+const DEFAULT_DRIVE_STEP: Duration = Duration::from_millis(200);
+
 #[derive(PartialEq)]
 enum State {
     Car,
     Goal,
     Wall,
+    Weighted,
     Remove,
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Copy)]
+enum Algorithm {
+    AStar,
+    JumpPoint,
+    Bidirectional,
+    //keeps the search between runs and repairs it with the cells changed since the last one
+    DStarLite,
+}
+
+#[derive(PartialEq, Clone, Copy)]
 pub enum Heuristic {
     Euclidean,
     Manhattan,
     Chebyshev,
+    Octile,
+    Dijkstra,
+}
+
+impl grid::Heuristic for Heuristic {
+    fn estimate(&self, pos: (usize, usize), goal: (usize, usize)) -> f32 {
+        match self {
+            Heuristic::Euclidean => heuristic::Euclidean.estimate(pos, goal),
+            Heuristic::Manhattan => heuristic::Manhattan.estimate(pos, goal),
+            Heuristic::Chebyshev => heuristic::Chebyshev.estimate(pos, goal),
+            Heuristic::Octile => heuristic::Octile.estimate(pos, goal),
+            Heuristic::Dijkstra => heuristic::Zero.estimate(pos, goal),
+        }
+    }
+}
+
+//what the Random button fills the grid with
+#[derive(PartialEq, Clone, Copy)]
+pub enum Generator {
+    Walls,
+    Maze(MazeAlgorithm),
+    Cave,
+    Rooms,
 }
 
 pub struct FrontEnd {
     grid: Grid,
     state: State,
+    weight: u8,
     wall_percentage: usize,
+    generator: Generator,
+    braid: f32,
+    cave_rules: CaveRules,
+    room_rules: RoomRules,
     heuristic: Heuristic,
+    algorithm: Algorithm,
+    planner: Option<DStarLite<Heuristic>>,
+    changed_cells: Vec<(usize, usize)>,
+    //the last path found from the car to the goal, empty if the grid changed since then
+    path: Vec<(usize, usize)>,
     status_msg: String,
-    compatibility_mode: bool,
+    theme: Theme,
+    //if the glyphs the terminal can't draw two columns wide are replaced when it starts
+    detect_glyphs: bool,
+    //the key of every action, which can be changed in the config file
+    keys: Vec<(KeyCode, Action)>,
+    //the time between the expansions of an animated search, zero if it isn't animated
+    delay: Duration,
+    //the time the car stays in every cell while it's driven
+    drive_step: Duration,
+    //where the Save button writes the map
+    output_path: String,
+    //the seed of the next random map and the one of the map in the grid if it's random
+    seed: u64,
+    map_seed: Option<u64>,
+    //the part of the grid that is shown and where the view started being dragged
+    viewport: Viewport,
+    drag_start: Option<(u16, u16)>,
+    //the columns and rows of the terminal, which are updated when it's resized
+    term_size: (u16, u16),
+    //the cell where the keyboard uses the tools
+    cursor: (usize, usize),
+    show_help: bool,
 }
 
 impl FrontEnd {
@@ -41,23 +115,117 @@ impl FrontEnd {
         FrontEnd {
             grid,
             state: State::Wall,
+            weight: 5,
             wall_percentage,
+            generator: Generator::Walls,
+            braid: 0.0,
+            cave_rules: CaveRules::default(),
+            room_rules: RoomRules::default(),
             heuristic,
+            algorithm: Algorithm::AStar,
+            planner: None,
+            changed_cells: Vec::new(),
+            path: Vec::new(),
             status_msg: String::new(),
-            compatibility_mode: false,
+            theme: Theme::default(),
+            detect_glyphs: true,
+            keys: keys::get_default_keys(),
+            delay: Duration::from_millis(0),
+            drive_step: DEFAULT_DRIVE_STEP,
+            output_path: String::from(DEFAULT_OUTPUT_PATH),
+            seed: thread_rng().gen(),
+            map_seed: None,
+            viewport: Viewport::default(),
+            drag_start: None,
+            term_size: (0, 0),
+            cursor: (0, 0),
+            show_help: false,
         }
     }
 
     pub fn run(&mut self) -> Result<()> {
-        execute!(stdout(), Hide, EnableMouseCapture, EnterAlternateScreen)?;
+        //the toolbar and the status bar are cut instead of wrapped when the terminal is narrow
+        execute!(
+            stdout(),
+            Hide,
+            EnableMouseCapture,
+            EnterAlternateScreen,
+            DisableLineWrap
+        )?;
         enable_raw_mode()?;
+        self.term_size = size()?;
+        if self.detect_glyphs {
+            self.replace_unsupported_glyphs()?;
+        }
         loop {
             self.draw_screen()?;
             self.process_event()?;
         }
     }
 
+    //draws the car and the goal with letters, for the terminals that can't show the emojis
     pub fn use_compatibility_mode(&mut self) {
-        self.compatibility_mode = true;
+        self.theme.use_ascii_glyphs();
+        self.detect_glyphs = false;
+    }
+
+    //by default the glyphs that the terminal draws with the wrong width are replaced
+    pub fn set_glyph_detection(&mut self, detect: bool) {
+        self.detect_glyphs = detect;
+    }
+
+    pub fn set_output_path(&mut self, path: &str) {
+        self.output_path = path.to_string();
+    }
+
+    pub fn set_generator(&mut self, generator: Generator) {
+        self.generator = generator;
+    }
+
+    //the portion of the dead ends removed from the mazes
+    pub fn set_braid(&mut self, braid: f32) {
+        assert!((0.0..=1.0).contains(&braid));
+        self.braid = braid;
+    }
+
+    pub fn set_cave_rules(&mut self, rules: CaveRules) {
+        self.cave_rules = rules;
+    }
+
+    pub fn set_room_rules(&mut self, rules: RoomRules) {
+        self.room_rules = rules;
+    }
+
+    //the random maps are generated with consecutive seeds starting with this one
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    pub fn set_animation_delay(&mut self, delay: Duration) {
+        self.delay = delay;
+    }
+
+    fn get_key(&self, action: Action) -> KeyCode {
+        self.keys
+            .iter()
+            .find(|(_, bound)| *bound == action)
+            .map_or_else(|| action.get_default_key(), |&(key, _)| key)
+    }
+
+    //the action of the key while something is played or while the grid is edited
+    fn get_action(&self, key: KeyCode, playback: bool) -> Option<Action> {
+        self.keys
+            .iter()
+            .find(|(bound, action)| *bound == key && action.is_playback() == playback)
+            .map(|&(_, action)| action)
+    }
+
+    //the keys of the actions followed by what they do, like "Space: pause  n: step"
+    fn get_hint(&self, actions: &[(Action, &str)]) -> String {
+        actions
+            .iter()
+            .map(|&(action, hint)| format!("{}: {}", get_key_name(self.get_key(action)), hint))
+            .collect::<Vec<_>>()
+            .join("  ")
     }
 }
@@ -1,52 +1,321 @@
-use super::{FrontEnd, Heuristic};
+use super::keys::Action;
+use super::{Algorithm, FrontEnd};
+use crossterm::{
+    event::{poll, Event, KeyEvent},
+    Result,
+};
+use grid::{Agent, AgentStatus, DStarLite, Movement, SearchResult};
 use std::cmp::{max, min};
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+const MIN_DELAY: Duration = Duration::from_millis(1);
+const MAX_DELAY: Duration = Duration::from_millis(2000);
 
 impl FrontEnd {
-    pub(super) fn run_simulation(&mut self) {
+    pub(super) fn run_simulation(&mut self) -> Result<()> {
         self.grid.clear_path();
         if !self.check_valid_state() {
             self.status_msg = String::from("A goal and a car must be in the grid");
-            return;
+            return Ok(());
         }
-        let heuristic_fn = match self.heuristic {
-            Heuristic::Euclidean => get_euclidean_dist,
-            Heuristic::Manhattan => get_manhattan_dist,
-            Heuristic::Chebyshev => get_chebyshev_dist,
-        };
+        let (car, goal) = (self.grid.get_car().unwrap(), self.grid.get_goal().unwrap());
+        //the time isn't meaningful if the search was animated
+        let animated = !self.delay.is_zero()
+            && matches!(self.algorithm, Algorithm::AStar | Algorithm::JumpPoint);
         let time = Instant::now();
-        let path_result = self.grid.find_path(heuristic_fn);
+        let search_result = match self.algorithm {
+            _ if animated => self.animate_search(car, goal)?,
+            Algorithm::AStar => self.grid.search(car, goal, &self.heuristic),
+            Algorithm::JumpPoint => self.grid.jump_point_search(car, goal, &self.heuristic),
+            Algorithm::Bidirectional => self.grid.bidirectional_search(car, goal, &self.heuristic),
+            Algorithm::DStarLite => self.replan(car, goal),
+        };
         let time_used = time.elapsed();
-        match path_result {
+        self.grid.clear_path();
+        self.grid.draw_search(&search_result);
+        self.path = search_result
+            .get_path()
+            .map_or_else(Vec::new, |path_result| path_result.get_path().to_vec());
+        match search_result.get_path() {
             None => self.status_msg = String::from("Couldn't find a path"),
             Some(path_result) => {
-                self.status_msg =
-                    format!(
-                    "Path with length {1} found! {0} cells were explored. Approximate time used: {2} μs",
-                    path_result.get_n_explored(), path_result.get_path_length(), time_used.as_micros()
+                self.status_msg = format!(
+                    "Path with length {1} and cost {2} found! {0} cells were explored.",
+                    search_result.get_n_explored(),
+                    path_result.get_path_length(),
+                    path_result.get_cost()
                 );
+                if !animated {
+                    self.status_msg +=
+                        &format!(" Approximate time used: {} μs", time_used.as_micros());
+                }
             }
         }
+        Ok(())
     }
 
-    fn check_valid_state(&self) -> bool {
-        self.grid.has_car() && self.grid.has_goal()
+    //draws the search after every expansion until it finishes
+    //it can be paused, advanced while paused, sped up, slowed down and skipped to the end
+    fn animate_search(
+        &mut self,
+        car: (usize, usize),
+        goal: (usize, usize),
+    ) -> Result<SearchResult> {
+        //the search borrows the grid, so the steps are drawn in the original one
+        let grid = self.grid.clone();
+        let heuristic = self.heuristic;
+        let mut search = if self.algorithm == Algorithm::JumpPoint {
+            grid.jump_point_search_steps(car, goal, &heuristic)
+        } else {
+            grid.search_steps(car, goal, &heuristic)
+        };
+        let mut paused = false;
+        while !search.is_finished() {
+            if !paused {
+                search.step();
+            }
+            self.grid.draw_search_steps(&search);
+            self.status_msg = format!(
+                "{} cells explored{}. {} ({} ms)",
+                search.get_closed_set().len(),
+                if paused { " (paused)" } else { "" },
+                self.get_hint(&[
+                    (Action::Pause, "pause"),
+                    (Action::Step, "step"),
+                    (Action::Faster, "faster"),
+                    (Action::Slower, "slower"),
+                    (Action::Stop, "skip"),
+                ]),
+                self.delay.as_millis()
+            );
+            self.draw_screen()?;
+            //while paused it waits until a key is pressed
+            if !paused && !poll(self.delay)? {
+                continue;
+            }
+            if let Event::Key(KeyEvent { code, .. }) = self.read_event()? {
+                match self.get_action(code, true) {
+                    Some(Action::Pause) => paused = !paused,
+                    Some(Action::Step) if paused => {
+                        search.step();
+                    }
+                    Some(Action::Faster) => self.delay = max(self.delay / 2, MIN_DELAY),
+                    Some(Action::Slower) => self.delay = min(self.delay * 2, MAX_DELAY),
+                    Some(Action::Stop) => break,
+                    _ => {}
+                }
+            }
+        }
+        Ok(search.into_result())
     }
-}
 
-fn get_manhattan_dist(pos1: (usize, usize), pos2: (usize, usize)) -> f32 {
-    (max(pos1.0, pos2.0) - min(pos1.0, pos2.0) + max(pos1.1, pos2.1) - min(pos1.1, pos2.1)) as f32
-}
+    //plays the car moving along the last path found, showing what its sensors read in every cell
+    //it can be paused, advanced while paused, restarted, sped up, slowed down and stopped
+    //it waits next to the goal until it's restarted or stopped
+    pub(super) fn drive(&mut self) -> Result<()> {
+        if self.path.len() < 2 || self.grid.get_car() != self.path.first().copied() {
+            self.status_msg = String::from("Run a search to find a path for the car first");
+            return Ok(());
+        }
+        let path = std::mem::take(&mut self.path);
+        //the index of the car in the path
+        let mut i = 0;
+        let mut paused = false;
+        loop {
+            let arrived = i == path.len() - 2;
+            self.status_msg = format!(
+                "{}Sensors: {}. {} ({} ms)",
+                if arrived {
+                    "The car is next to the goal! "
+                } else if paused {
+                    "(paused) "
+                } else {
+                    ""
+                },
+                self.grid.get_perception().unwrap(),
+                self.get_hint(&[
+                    (Action::Pause, "pause"),
+                    (Action::Step, "step"),
+                    (Action::Restart, "restart"),
+                    (Action::Faster, "faster"),
+                    (Action::Slower, "slower"),
+                    (Action::Stop, "stop"),
+                ]),
+                self.drive_step.as_millis()
+            );
+            self.draw_screen()?;
+            //while paused or next to the goal it waits until a key is pressed
+            if !paused && !arrived && !poll(self.drive_step)? {
+                i += 1;
+                self.move_car(path[i]);
+                continue;
+            }
+            if let Event::Key(KeyEvent { code, .. }) = self.read_event()? {
+                match self.get_action(code, true) {
+                    Some(Action::Pause) => paused = !paused,
+                    Some(Action::Step) if paused && !arrived => {
+                        i += 1;
+                        self.move_car(path[i]);
+                    }
+                    Some(Action::Restart) => {
+                        i = 0;
+                        self.move_car(path[0]);
+                        self.grid.draw_trajectory(&path);
+                    }
+                    Some(Action::Faster) => self.drive_step = max(self.drive_step / 2, MIN_DELAY),
+                    Some(Action::Slower) => self.drive_step = min(self.drive_step * 2, MAX_DELAY),
+                    Some(Action::Stop) => break,
+                    _ => {}
+                }
+            }
+        }
+        self.status_msg = format!("Sensors: {}", self.grid.get_perception().unwrap());
+        //the path can be played again if the car is still at its start
+        if i == 0 {
+            self.path = path;
+        }
+        Ok(())
+    }
 
-fn get_euclidean_dist(pos1: (usize, usize), pos2: (usize, usize)) -> f32 {
-    let pos1 = (pos1.0 as f32, pos1.1 as f32);
-    let pos2 = (pos2.0 as f32, pos2.1 as f32);
-    ((pos1.0 - pos2.0).powi(2) + (pos1.1 - pos2.1).powi(2)).sqrt()
-}
+    //the planner is told about both cells, so it can be repaired after the car moves
+    fn move_car(&mut self, pos: (usize, usize)) {
+        let car = self.grid.get_car().unwrap();
+        if car != pos {
+            self.changed_cells.push(car);
+            self.changed_cells.push(pos);
+            self.grid.set_car(pos.0, pos.1);
+        }
+    }
 
-fn get_chebyshev_dist(pos1: (usize, usize), pos2: (usize, usize)) -> f32 {
-    max(
-        max(pos1.0, pos2.0) - min(pos1.0, pos2.0),
-        max(pos1.1, pos2.1) - min(pos1.1, pos2.1),
-    ) as f32
+    //moves a car that only knows the walls its sensors find until it reaches the goal
+    //it can be paused, advanced while paused, sped up, slowed down and stopped, and the map shown
+    //can be switched between the real one and the one the agent believes
+    pub(super) fn run_agent(&mut self) -> Result<()> {
+        self.grid.clear_path();
+        if !self.check_valid_state() {
+            self.status_msg = String::from("A goal and a car must be in the grid");
+            return Ok(());
+        }
+        let mut agent = Agent::new(&self.grid, self.heuristic);
+        let (mut paused, mut show_belief) = (false, true);
+        while !agent.is_finished() {
+            if !paused {
+                agent.step(&self.grid);
+            }
+            let mut view = if show_belief {
+                agent.get_belief().clone()
+            } else {
+                self.grid.clone()
+            };
+            let pos = agent.get_position();
+            if view.get_goal() != Some(pos) {
+                view.set_car(pos.0, pos.1);
+            }
+            view.draw_trajectory(agent.get_trajectory());
+            self.status_msg = format!(
+                "{} map. Travelled {} with {} replans{}. {}",
+                if show_belief { "Believed" } else { "Real" },
+                agent.get_distance(),
+                agent.get_n_replans(),
+                if paused { " (paused)" } else { "" },
+                self.get_hint(&[
+                    (Action::Pause, "pause"),
+                    (Action::Step, "step"),
+                    (Action::SwitchMap, "switch map"),
+                    (Action::Faster, "faster"),
+                    (Action::Slower, "slower"),
+                    (Action::Stop, "stop"),
+                ]),
+            );
+            self.draw_screen_with(&view)?;
+            //while paused it waits until a key is pressed
+            if !paused && !poll(self.drive_step)? {
+                continue;
+            }
+            if let Event::Key(KeyEvent { code, .. }) = self.read_event()? {
+                match self.get_action(code, true) {
+                    Some(Action::Pause) => paused = !paused,
+                    Some(Action::Step) if paused => {
+                        agent.step(&self.grid);
+                    }
+                    Some(Action::SwitchMap) => show_belief = !show_belief,
+                    Some(Action::Faster) => self.drive_step = max(self.drive_step / 2, MIN_DELAY),
+                    Some(Action::Slower) => self.drive_step = min(self.drive_step * 2, MAX_DELAY),
+                    Some(Action::Stop) => break,
+                    _ => {}
+                }
+            }
+        }
+        //the trajectory is left in the real map
+        self.grid.draw_trajectory(agent.get_trajectory());
+        self.status_msg = match agent.get_status() {
+            //both costs are the ones of the real map, but the agent planned without knowing
+            //the costs of the cells, only the walls it sensed
+            AgentStatus::Arrived => {
+                let travelled = format!(
+                    "The agent reached the goal with a cost of {} and {} replans",
+                    agent.get_distance(),
+                    agent.get_n_replans()
+                );
+                match self.get_omniscient_cost() {
+                    Some(optimum) => format!(
+                        "{}. Knowing the walls and costs it would cost {} ({:.0}% less)",
+                        travelled,
+                        optimum,
+                        (1.0 - optimum / agent.get_distance()) * 100.0
+                    ),
+                    None => travelled,
+                }
+            }
+            AgentStatus::Stuck => format!(
+                "The agent found out there isn't a path after travelling {} with {} replans",
+                agent.get_distance(),
+                agent.get_n_replans()
+            ),
+            _ => format!(
+                "The agent was stopped after travelling {} with {} replans",
+                agent.get_distance(),
+                agent.get_n_replans()
+            ),
+        };
+        Ok(())
+    }
+
+    //the cost of the best path with the same moves the agent can make
+    fn get_omniscient_cost(&self) -> Option<f32> {
+        let mut grid = self.grid.clone();
+        grid.set_movement(Movement::FourConnected);
+        grid.search(
+            self.grid.get_car().unwrap(),
+            self.grid.get_goal().unwrap(),
+            &self.heuristic,
+        )
+        .into_path()
+        .map(|path| path.get_cost())
+    }
+
+    //reuses the planner of the last run if the goal is the same
+    fn replan(&mut self, car: (usize, usize), goal: (usize, usize)) -> SearchResult {
+        match &mut self.planner {
+            Some(planner) if planner.get_goal() == goal => {
+                if planner.get_start() != car {
+                    planner.move_start(car);
+                }
+                planner.update_cells(&self.grid, &self.changed_cells);
+            }
+            _ => self.planner = Some(DStarLite::new(&self.grid, car, goal, self.heuristic)),
+        }
+        self.changed_cells.clear();
+        self.planner.as_mut().unwrap().plan(&self.grid)
+    }
+
+    //the planner can only be repaired after changes to single cells
+    pub(super) fn reset_planner(&mut self) {
+        self.planner = None;
+        self.changed_cells.clear();
+    }
+
+    fn check_valid_state(&self) -> bool {
+        self.grid.has_car() && self.grid.has_goal()
+    }
 }
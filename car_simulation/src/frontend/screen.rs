@@ -1,82 +1,324 @@
-use super::{FrontEnd, Heuristic, State};
+use super::keys::{get_key_name, get_key_symbol, Action};
+use super::{Algorithm, FrontEnd, Generator, Heuristic, State};
 use crossterm::{
-    cursor::MoveTo,
-    queue,
-    style::Styler,
-    terminal::{size, Clear, ClearType},
+    cursor::{position, Hide, MoveTo, Show},
+    execute, queue,
+    style::{Print, Styler},
+    terminal::{Clear, ClearType},
     Result,
 };
+use grid::{Grid, MazeAlgorithm, Movement, Viewport};
 use std::io::{stdout, Write};
+use std::ops::RangeInclusive;
+
+//a button of the toolbar and where it's drawn
+pub(super) struct Button {
+    pub(super) row: u16,
+    pub(super) columns: RangeInclusive<u16>,
+    pub(super) action: Action,
+}
+
+//a text of the toolbar, the ones without an action aren't buttons
+struct Label {
+    text: String,
+    action: Option<Action>,
+    bold: bool,
+}
+
+fn button<T: Into<String>>(text: T, action: Action, bold: bool) -> Label {
+    Label {
+        text: text.into(),
+        action: Some(action),
+        bold,
+    }
+}
+
+fn get_width(label: &Label) -> u16 {
+    label.text.chars().count() as u16
+}
 
 impl FrontEnd {
-    pub(super) fn draw_screen(&mut self) -> Result<()> {
+    pub(super) fn draw_screen(&self) -> Result<()> {
+        self.draw_screen_with(&self.grid)
+    }
+
+    //draws another grid in the place of the one being edited, like the map an agent believes in
+    pub(super) fn draw_screen_with(&self, grid: &Grid) -> Result<()> {
         queue!(stdout(), Clear(ClearType::All))?;
-        self.draw_grid()?;
+        self.draw_grid(grid)?;
         self.draw_buttons()?;
+        self.draw_keys()?;
         self.draw_status_bar()?;
+        if self.show_help {
+            self.draw_help()?;
+            queue!(stdout(), Hide)?;
+        } else {
+            self.draw_cursor(grid)?;
+        }
         stdout().flush()?;
         Ok(())
     }
 
-    fn draw_grid(&mut self) -> Result<()> {
+    //the grid may have changed since the view was moved, for example while it's resized
+    fn get_view(&self, grid: &Grid) -> Viewport {
+        let mut view = self.viewport;
+        view.clamp(grid, self.get_grid_area());
+        view
+    }
+
+    fn draw_grid(&self, grid: &Grid) -> Result<()> {
         queue!(stdout(), MoveTo(0, 0))?;
-        if self.compatibility_mode {
+        write!(
+            stdout(),
+            "{}",
+            grid.draw_viewport(&self.get_view(grid), self.get_grid_area(), &self.theme)
+        )?;
+        Ok(())
+    }
+
+    fn draw_buttons(&self) -> Result<()> {
+        let first_row = self.get_buttons_row();
+        for (i, line) in self.get_toolbar_lines().into_iter().enumerate() {
+            for (column, label) in line {
+                queue!(stdout(), MoveTo(column, first_row + 2 * i as u16))?;
+                match label.action {
+                    None => write!(stdout(), "{}", label.text)?,
+                    Some(_) if label.bold => write!(stdout(), "{}", label.text.bold())?,
+                    Some(_) => write!(stdout(), "{}", label.text.negative())?,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    //the key of every button is below it
+    fn draw_keys(&self) -> Result<()> {
+        for button in self.get_buttons() {
+            queue!(stdout(), MoveTo(*button.columns.start(), button.row + 1))?;
             write!(
                 stdout(),
                 "{}",
-                self.grid
-                    .to_string()
-                    .replace("🚗", "CC")
-                    .replace("🏁", "GG")
+                get_key_symbol(self.get_key(button.action)).dim()
             )?;
-        } else {
-            write!(stdout(), "{}", self.grid)?;
         }
         Ok(())
     }
 
-    fn draw_buttons(&mut self) -> Result<()> {
-        queue!(stdout(), MoveTo(0, size()?.1 - 2))?;
-        write!(
-            stdout(),
-            "{0}Rows{1} {0}Columns{1} {2} {3} {4} {5} {6} {7} {8} {9} {10}\n\r",
-            "--".negative(),
-            "++".negative(),
-            if self.state == State::Car {
-                "Car".bold()
-            } else {
-                "Car".negative()
-            },
-            if self.state == State::Goal {
-                "Goal".bold()
-            } else {
-                "Goal".negative()
-            },
-            if self.state == State::Wall {
-                "Wall".bold()
-            } else {
-                "Wall".negative()
-            },
-            if self.state == State::Remove {
-                "Remove".bold()
-            } else {
-                "Remove".negative()
+    //the labels in groups that are kept in the same line of the toolbar
+    //the selected options are bold, the rest of the buttons are negative
+    fn get_toolbar_groups(&self) -> Vec<Vec<Label>> {
+        let rows = vec![
+            button("--", Action::RemoveRow, false),
+            Label {
+                text: String::from("Rows"),
+                action: None,
+                bold: false,
             },
-            "Clear".negative(),
-            "Random".negative(),
-            match self.heuristic {
-                Heuristic::Euclidean => "Euclidean".bold(),
-                Heuristic::Manhattan => "Manhattan".bold(),
-                Heuristic::Chebyshev => "Chebyshev".bold(),
+            button("++", Action::AddRow, false),
+        ];
+        let columns = vec![
+            button("--", Action::RemoveColumn, false),
+            Label {
+                text: String::from("Columns"),
+                action: None,
+                bold: false,
             },
-            "Run".negative(),
-            "Quit".negative()
-        )?;
+            button("++", Action::AddColumn, false),
+        ];
+        let generator = match self.generator {
+            Generator::Walls => "Walls    ",
+            Generator::Maze(MazeAlgorithm::RecursiveBacktracker) => "Backtrack",
+            Generator::Maze(MazeAlgorithm::Prim) => "Prim     ",
+            Generator::Maze(MazeAlgorithm::Kruskal) => "Kruskal  ",
+            Generator::Maze(MazeAlgorithm::RecursiveDivision) => "Division ",
+            Generator::Cave => "Cave     ",
+            Generator::Rooms => "Rooms    ",
+        };
+        let algorithm = match self.algorithm {
+            Algorithm::AStar => "A*    ",
+            Algorithm::JumpPoint => "JPS   ",
+            Algorithm::Bidirectional => "Bidir ",
+            Algorithm::DStarLite => "D*Lite",
+        };
+        let heuristic = match self.heuristic {
+            Heuristic::Euclidean => "Euclidean",
+            Heuristic::Manhattan => "Manhattan",
+            Heuristic::Chebyshev => "Chebyshev",
+            Heuristic::Octile => "Octile   ",
+            Heuristic::Dijkstra => "Dijkstra ",
+        };
+        let movement = match self.grid.get_movement() {
+            Movement::FourConnected => "4-way ",
+            Movement::EightConnected => "8-way ",
+            Movement::EightConnectedNoCornerCutting => "8-safe",
+        };
+        let buttons = vec![
+            button("Car", Action::Car, self.state == State::Car),
+            button("Goal", Action::Goal, self.state == State::Goal),
+            button("Wall", Action::Wall, self.state == State::Wall),
+            button(
+                format!("Cost {}", self.weight),
+                Action::Cost,
+                self.state == State::Weighted,
+            ),
+            button("Remove", Action::Remove, self.state == State::Remove),
+            button("Clear", Action::Clear, false),
+            button("Random", Action::Random, false),
+            button(generator, Action::Generator, true),
+            button(algorithm, Action::Algorithm, true),
+            button(heuristic, Action::Heuristic, true),
+            button(movement, Action::Movement, true),
+            button("Run", Action::Run, false),
+            button("Drive", Action::Drive, false),
+            button("Agent", Action::Agent, false),
+            button("Save", Action::Save, false),
+            button("Quit", Action::Quit, false),
+        ];
+        let mut groups = vec![rows, columns];
+        groups.extend(buttons.into_iter().map(|button| vec![button]));
+        groups
+    }
+
+    //the labels of every line of the toolbar with the column where they start
+    //the groups are separated by a space and the ones that don't fit go to the next line
+    fn get_toolbar_lines(&self) -> Vec<Vec<(u16, Label)>> {
+        let mut lines = vec![Vec::new()];
+        let mut column = 0;
+        for group in self.get_toolbar_groups() {
+            let width: u16 = group.iter().map(get_width).sum();
+            if column > 0 && column + width > self.term_size.0 {
+                lines.push(Vec::new());
+                column = 0;
+            }
+            let line = lines.last_mut().unwrap();
+            for label in group {
+                let label_width = get_width(&label);
+                line.push((column, label));
+                column += label_width;
+            }
+            column += 1;
+        }
+        lines
+    }
+
+    //the buttons as they're drawn now, so the clicks are checked against them
+    pub(super) fn get_buttons(&self) -> Vec<Button> {
+        let first_row = self.get_buttons_row();
+        let mut buttons = Vec::new();
+        for (i, line) in self.get_toolbar_lines().into_iter().enumerate() {
+            for (column, label) in line {
+                if let Some(action) = label.action {
+                    buttons.push(Button {
+                        row: first_row + 2 * i as u16,
+                        columns: column..=column + get_width(&label) - 1,
+                        action,
+                    });
+                }
+            }
+        }
+        buttons
+    }
+
+    fn draw_status_bar(&self) -> Result<()> {
+        queue!(stdout(), MoveTo(0, self.term_size.1.saturating_sub(1)))?;
+        if let Some(seed) = self.map_seed {
+            write!(stdout(), "{} ", format!("Seed {}", seed).negative())?;
+        }
+        if self.status_msg.is_empty() {
+            let hint = format!(
+                "Press {} to see all the keys",
+                get_key_name(self.get_key(Action::Help))
+            );
+            write!(stdout(), "{}", hint.dim())?;
+        } else {
+            write!(stdout(), "{}", self.status_msg)?;
+        }
         Ok(())
     }
 
-    fn draw_status_bar(&mut self) -> Result<()> {
-        write!(stdout(), "{}", self.status_msg)?;
+    //a list of the keys over the grid, as many as fit above the toolbar
+    fn draw_help(&self) -> Result<()> {
+        let keys = [
+            ("Arrows", "Moves the cursor"),
+            ("Right drag", "Moves the view"),
+        ];
+        let lines = keys
+            .iter()
+            .map(|&(key, description)| (String::from(key), description))
+            .chain(Action::ALL.iter().map(|action| {
+                (
+                    get_key_name(self.get_key(*action)),
+                    action.get_description(),
+                )
+            }));
+        for (i, (key, description)) in lines.enumerate() {
+            let row = i as u16 + 1;
+            if row >= self.get_buttons_row() {
+                break;
+            }
+            queue!(stdout(), MoveTo(2, row))?;
+            write!(
+                stdout(),
+                "{}",
+                format!(" {:<10} {:<50} ", key, description).negative()
+            )?;
+        }
+        Ok(())
+    }
+
+    //the terminal cursor shows the cell of the keyboard cursor
+    fn draw_cursor(&self, grid: &Grid) -> Result<()> {
+        match self
+            .get_view(grid)
+            .get_position(grid, self.get_grid_area(), self.cursor)
+        {
+            Some((column, row)) => queue!(stdout(), MoveTo(column as u16, row as u16), Show)?,
+            None => queue!(stdout(), Hide)?,
+        }
         Ok(())
     }
+
+    //prints every glyph and asks the terminal where the cursor ended, so the emojis aren't used
+    //when they'd break the columns of the grid. If the terminal doesn't answer, which takes a
+    //couple of seconds, the glyphs are kept
+    pub(super) fn replace_unsupported_glyphs(&mut self) -> Result<()> {
+        let mut answered = true;
+        let replaced = self.theme.replace_glyphs(|glyph| {
+            if answered {
+                match get_drawn_width(glyph) {
+                    Ok(width) => return width == 2,
+                    Err(_) => answered = false,
+                }
+            }
+            true
+        });
+        if replaced {
+            self.status_msg = String::from(
+                "Some glyphs can't be drawn by this terminal and were replaced, --glyphs emoji keeps them",
+            );
+        }
+        Ok(())
+    }
+
+    //the columns and rows of the terminal above the toolbar and the status bar
+    pub(super) fn get_grid_area(&self) -> (usize, usize) {
+        (self.term_size.0 as usize, self.get_buttons_row() as usize)
+    }
+
+    //the first line of the toolbar, every line of it has the keys below
+    //and the status bar is the last row of the terminal
+    pub(super) fn get_buttons_row(&self) -> u16 {
+        let n_lines = self.get_toolbar_lines().len() as u16;
+        self.term_size.1.saturating_sub(2 * n_lines + 1)
+    }
+}
+
+fn get_drawn_width(glyph: &str) -> Result<u16> {
+    execute!(
+        stdout(),
+        MoveTo(0, 0),
+        Clear(ClearType::CurrentLine),
+        Print(glyph)
+    )?;
+    Ok(position()?.0)
 }
@@ -1,21 +1,58 @@
-use clap::{App, Arg, ArgMatches};
-use crossterm::{terminal::size, Result};
-use grid::Grid;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use grid::{CaveRules, Grid, MazeAlgorithm, Movement, RoomRules};
 mod frontend;
-use frontend::{FrontEnd, Heuristic};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+mod solve;
+use frontend::{FrontEnd, Generator, Heuristic, THEMES};
+use std::error::Error;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::time::Duration;
 
-fn main() -> Result<()> {
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
     let matches = get_args_matches();
-    let grid = get_grid(&matches)?;
-    let wall_percentage = get_wall_percentage(&matches);
+    if let Some(matches) = matches.subcommand_matches("solve") {
+        return solve::solve(matches);
+    }
+    let mut grid = get_grid(&matches)?;
+    let wall_percentage = get_wall_percentage(&matches)?;
     let heuristic = get_heuristic(&matches);
+    grid.set_movement(get_movement(&matches));
     let mut frontend = FrontEnd::new(grid, wall_percentage, heuristic);
-    if matches.is_present("compat") {
-        frontend.use_compatibility_mode();
+    frontend.load_config(matches.value_of("config"), matches.value_of("theme"))?;
+    match matches.value_of("glyphs") {
+        _ if matches.is_present("compat") => frontend.use_compatibility_mode(),
+        Some("ascii") => frontend.use_compatibility_mode(),
+        Some("emoji") => frontend.set_glyph_detection(false),
+        _ => {}
+    }
+    frontend.set_animation_delay(get_delay(&matches)?);
+    if let Some(algorithm) = get_maze_algorithm(&matches) {
+        frontend.set_generator(Generator::Maze(algorithm));
+    }
+    if matches.is_present("cave") {
+        frontend.set_cave_rules(get_cave_rules(&matches)?);
+        frontend.set_generator(Generator::Cave);
+    }
+    if matches.is_present("rooms") {
+        frontend.set_room_rules(get_room_rules(&matches)?);
+        frontend.set_generator(Generator::Rooms);
+    }
+    frontend.set_braid(get_braid(&matches)?);
+    if let Some(seed) = get_seed(&matches)? {
+        frontend.set_seed(seed);
     }
-    frontend.run()
+    if let Some(path) = matches.value_of("output") {
+        frontend.set_output_path(path);
+    }
+    frontend.run()?;
+    Ok(())
 }
 
 fn get_args_matches() -> ArgMatches<'static> {
@@ -36,20 +73,72 @@ fn get_args_matches() -> ArgMatches<'static> {
                 .long("random")
                 .takes_value(true)
                 .help("Sets the percentage of walls in a random generated map"),
-            Arg::with_name("euclidean")
-                .long("euclidean")
-                .conflicts_with("manhattan")
-                .conflicts_with("chebyshev")
-                .help("Uses euclidean distance as the heuristic function"),
-            Arg::with_name("manhattan")
-                .long("manhattan")
-                .help("Uses manhattan distance as the heuristic function. This is the default"),
-            Arg::with_name("chebyshev")
-                .long("chebyshev")
-                .help("Uses chebyshev distance as the heuristic function"),
+            Arg::with_name("maze")
+                .long("maze")
+                .takes_value(true)
+                .possible_values(&["backtracker", "prim", "kruskal", "division"])
+                .help("Makes the Random button generate mazes with the given algorithm instead of random walls"),
+            Arg::with_name("cave")
+                .long("cave")
+                .takes_value(true)
+                .min_values(0)
+                .value_name("rules")
+                .conflicts_with_all(&["maze", "rooms"])
+                .help("Makes the Random button generate caves with a cellular automaton. The rules can be given like B5678/S45678, where the numbers after B are the walls around an empty cell that make it a wall and the ones after S the walls around a wall that keep it. B5678/S45678 is the default"),
+            Arg::with_name("iterations")
+                .long("iterations")
+                .takes_value(true)
+                .requires("cave")
+                .help("Sets the times the rules of the caves are applied. The default is 5"),
+            Arg::with_name("rooms")
+                .long("rooms")
+                .conflicts_with("maze")
+                .help("Makes the Random button generate rooms joined by corridors"),
+            Arg::with_name("room_width")
+                .long("room-width")
+                .takes_value(true)
+                .requires("rooms")
+                .help("Sets the width of the rooms, or the range it's chosen from like 4-10, which is the default"),
+            Arg::with_name("room_height")
+                .long("room-height")
+                .takes_value(true)
+                .requires("rooms")
+                .help("Sets the height of the rooms, or the range it's chosen from like 3-6, which is the default"),
+            Arg::with_name("max_rooms")
+                .long("max-rooms")
+                .takes_value(true)
+                .requires("rooms")
+                .help("Sets the most rooms that are placed, the ones that would overlap are left out. The default is 12"),
+            Arg::with_name("braid")
+                .long("braid")
+                .takes_value(true)
+                .help("Sets the portion of the dead ends removed from the mazes, between 0 and 1. The default is 0"),
+            Arg::with_name("seed")
+                .long("seed")
+                .takes_value(true)
+                .help("Sets the seed of the first random map, the next ones use the following seeds. The seed of the map is shown in the status bar"),
+            Arg::with_name("delay")
+                .long("delay")
+                .takes_value(true)
+                .help("Animates the A* and JPS searches waiting the given milliseconds between expansions. While animating, space pauses, n advances a step, + and - change the speed and esc skips to the end, unless other keys are set in the config file"),
+            Arg::with_name("glyphs")
+                .long("glyphs")
+                .takes_value(true)
+                .possible_values(&["auto", "emoji", "ascii"])
+                .help("Sets how the car, the goal and the path are drawn. By default the terminal is asked if it draws them right and the ones it can't are drawn with letters. emoji always uses the glyphs of the theme and ascii draws the car and the goal with letters"),
             Arg::with_name("compat")
                 .long("compat")
-                .help("Uses a compatibility mode. Use this option if you can't see the car or the goal flag correctly"),
+                .conflicts_with("glyphs")
+                .help("Draws the car and the goal with letters, like --glyphs ascii"),
+            Arg::with_name("config")
+                .long("config")
+                .takes_value(true)
+                .help("Reads the colors, the glyphs and the keys from the given TOML file instead of car_simulation/config.toml in the config directory of the user"),
+            Arg::with_name("theme")
+                .long("theme")
+                .takes_value(true)
+                .possible_values(&THEMES)
+                .help("Sets the colors, replacing the theme of the config file. The colors changed in the file are kept"),
             Arg::with_name("file")
                 .long("file")
                 .short("f")
@@ -65,13 +154,80 @@ fn get_args_matches() -> ArgMatches<'static> {
                     -C. as the car
                     -G. as the goal
                     -X. as walls
-                    -another character as empty cells",
+                    -1 to 9. as cells with that cost to go through
+                    -another character as empty cells
+                The characters after the last column and the lines after the last row are comments
+                Files ending in .map are read in the format of the MovingAI benchmarks instead",
                 ),
+            Arg::with_name("output")
+                .long("output")
+                .short("o")
+                .takes_value(true)
+                .help("Sets the file where the Save button writes the map. The default is saved_map. Files ending in .map are written in the format of the MovingAI benchmarks"),
         ])
+        .args(&get_search_args())
+        .subcommand(
+            SubCommand::with_name("solve")
+                .about("Searches a path in a map without the interface and prints it, so it can be used in scripts. The positions of the path are printed as a row and a column, counting from 0, like --start and --goal take them")
+                .args(&get_search_args())
+                .args(&[
+                    Arg::with_name("map")
+                        .required(true)
+                        .help("The map to solve, in the same formats read by --file. It can be of any size"),
+                    Arg::with_name("algorithm")
+                        .long("algorithm")
+                        .takes_value(true)
+                        .possible_values(&["astar", "jps", "bidirectional"])
+                        .help("Sets the search algorithm. The default is astar"),
+                    Arg::with_name("start")
+                        .long("start")
+                        .takes_value(true)
+                        .value_name("row,column")
+                        .help("Sets where the search starts instead of the car of the map, counting from 0. Needed if the map has no car, like the MovingAI maps"),
+                    Arg::with_name("goal")
+                        .long("goal")
+                        .takes_value(true)
+                        .value_name("row,column")
+                        .help("Sets the goal of the search instead of the one of the map, counting from 0. Needed if the map has no goal, like the MovingAI maps"),
+                    Arg::with_name("json")
+                        .long("json")
+                        .help("Prints the result as JSON instead of plain text"),
+                ]),
+        )
         .get_matches()
 }
 
-fn get_grid(matches: &ArgMatches) -> Result<Grid> {
+//the options shared by the interface and the solve subcommand
+fn get_search_args() -> Vec<Arg<'static, 'static>> {
+    vec![
+        Arg::with_name("euclidean")
+            .long("euclidean")
+            .conflicts_with_all(&["manhattan", "chebyshev", "octile", "dijkstra"])
+            .help("Uses euclidean distance as the heuristic function"),
+        Arg::with_name("manhattan")
+            .long("manhattan")
+            .help("Uses manhattan distance as the heuristic function. This is the default"),
+        Arg::with_name("chebyshev")
+            .long("chebyshev")
+            .conflicts_with_all(&["manhattan", "octile", "dijkstra"])
+            .help("Uses chebyshev distance as the heuristic function"),
+        Arg::with_name("octile")
+            .long("octile")
+            .conflicts_with_all(&["manhattan", "dijkstra"])
+            .help("Uses octile distance as the heuristic function"),
+        Arg::with_name("dijkstra")
+            .long("dijkstra")
+            .conflicts_with("manhattan")
+            .help("Doesn't use any heuristic function, which is equivalent to Dijkstra's algorithm"),
+        Arg::with_name("movement")
+            .long("movement")
+            .takes_value(true)
+            .possible_values(&["4", "8", "8-safe"])
+            .help("Sets how the car can move. 8-safe allows diagonal moves but not cutting the corner of a wall. The default is 4"),
+    ]
+}
+
+fn get_grid(matches: &ArgMatches) -> Result<Grid, Box<dyn Error>> {
     if let Some(file) = matches.value_of("file") {
         get_grid_from_file(file)
     } else {
@@ -79,93 +235,160 @@ fn get_grid(matches: &ArgMatches) -> Result<Grid> {
     }
 }
 
-fn get_grid_from_file(path: &str) -> Result<Grid> {
-    let file = File::open(path)?;
-    let mut buffer = BufReader::new(file).lines();
-    let m = buffer.next().unwrap()?.parse::<usize>()?;
-    let n = buffer.next().unwrap()?.parse::<usize>()?;
-    check_valid_size(m, n)?;
-    let mut grid = Grid::new(m, n);
-    for i in 0..m {
-        let row = buffer.next().unwrap()?;
-        let mut row_chars = row.chars();
-        for j in 0..n {
-            let c = row_chars.next().unwrap();
-            match c {
-                'C' => grid.set_car(j, i),
-                'G' => grid.set_goal(j, i),
-                'X' => grid.set_wall(j, i),
-                _ => {}
-            }
-        }
+fn get_grid_from_file(path: &str) -> Result<Grid, Box<dyn Error>> {
+    let map = fs::read_to_string(path)
+        .map_err(|err| format!("Couldn't read the map {}: {}", path, err))?;
+    let grid = if is_moving_ai_map(path) {
+        Grid::from_moving_ai(&map)
+    } else {
+        map.parse::<Grid>()
     }
+    .map_err(|err| format!("Couldn't load the map {}: {}", path, err))?;
     Ok(grid)
 }
 
-fn get_grid_from_args(matches: &ArgMatches) -> Result<Grid> {
-    let (m, n) = get_grid_size(&matches);
-    check_valid_size(m, n)?;
+//the maps of the MovingAI benchmarks use their own format
+fn is_moving_ai_map(path: &str) -> bool {
+    path.ends_with(".map")
+}
+
+fn get_grid_from_args(matches: &ArgMatches) -> Result<Grid, Box<dyn Error>> {
+    let (m, n) = get_grid_size(matches)?;
     Ok(Grid::new(m, n))
 }
 
-fn get_grid_size(matches: &ArgMatches) -> (usize, usize) {
+fn get_grid_size(matches: &ArgMatches) -> Result<(usize, usize), String> {
     let m = matches.value_of("m").unwrap_or("10");
     let n = matches.value_of("n").unwrap_or("10");
     let m = match m.parse() {
-        Err(_) | Ok(0) => {
-            eprintln!("The -m parameter must be a positive integer");
-            std::process::exit(-1);
-        }
+        Err(_) | Ok(0) => return Err(String::from("The -m parameter must be a positive integer")),
         Ok(num) => num,
     };
     let n = match n.parse() {
-        Err(_) | Ok(0) => {
-            eprintln!("The -n parameter must be a positive integer");
-            std::process::exit(-1);
-        }
+        Err(_) | Ok(0) => return Err(String::from("The -n parameter must be a positive integer")),
         Ok(num) => num,
     };
 
-    (m, n)
-}
-
-fn check_valid_size(m: usize, n: usize) -> Result<()> {
-    let term_size = size()?;
-    if m > term_size.1 as usize - 4 {
-        eprintln!(
-            "There isn't space in your terminal for a grid with a height of {}",
-            m
-        );
-        eprintln!(
-            "The maximum for the actual size of your terminal is {}",
-            term_size.1 as usize - 4
-        );
-        std::process::exit(-1);
-    } else if n > term_size.0 as usize / 2 - 2 {
-        eprintln!(
-            "There isn't space in your terminal for a grid with a width of {}",
-            n
-        );
-        eprintln!(
-            "The maximum for the actual size of your terminal is {}",
-            term_size.0 as usize / 2 - 2
-        );
-        std::process::exit(-1);
-    }
-    Ok(())
+    Ok((m, n))
 }
 
-fn get_wall_percentage(matches: &ArgMatches) -> usize {
+fn get_wall_percentage(matches: &ArgMatches) -> Result<usize, String> {
     let wall_percentage = matches.value_of("wall_percentage").unwrap_or("15");
-    let wall_percentage = match wall_percentage.parse() {
-        Ok(num @ 0..=100) => num,
-        _ => {
-            eprintln!("The -r parameter must be a positive integer between 0 and 100");
-            std::process::exit(-1);
-        }
+    match wall_percentage.parse() {
+        Ok(num @ 0..=100) => Ok(num),
+        _ => Err(String::from(
+            "The -r parameter must be a positive integer between 0 and 100",
+        )),
+    }
+}
+
+fn get_maze_algorithm(matches: &ArgMatches) -> Option<MazeAlgorithm> {
+    match matches.value_of("maze") {
+        Some("backtracker") => Some(MazeAlgorithm::RecursiveBacktracker),
+        Some("prim") => Some(MazeAlgorithm::Prim),
+        Some("kruskal") => Some(MazeAlgorithm::Kruskal),
+        Some("division") => Some(MazeAlgorithm::RecursiveDivision),
+        _ => None,
+    }
+}
+
+//the rules are written like B5678/S45678
+fn get_cave_rules(matches: &ArgMatches) -> Result<CaveRules, String> {
+    let mut rules = CaveRules::default();
+    if let Some(value) = matches.value_of("cave") {
+        let invalid = || {
+            String::from(
+                "The --cave parameter must be the rules of the caves, like B5678/S45678, with numbers from 0 to 8",
+            )
+        };
+        let mut parts = value.split('/');
+        let (birth, survival) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(birth), Some(survival), None) => (birth, survival),
+            _ => return Err(invalid()),
+        };
+        rules.birth = parse_neighbour_counts(birth, 'B').ok_or_else(invalid)?;
+        rules.survival = parse_neighbour_counts(survival, 'S').ok_or_else(invalid)?;
+    }
+    if let Some(iterations) = matches.value_of("iterations") {
+        rules.iterations = iterations
+            .parse()
+            .map_err(|_| String::from("The --iterations parameter must be a positive integer"))?;
+    }
+    Ok(rules)
+}
+
+//the letter followed by digits from 0 to 8, like B5678
+fn parse_neighbour_counts(part: &str, letter: char) -> Option<Vec<usize>> {
+    let mut chars = part.chars();
+    if chars.next()?.to_ascii_uppercase() != letter {
+        return None;
+    }
+    chars
+        .map(|c| c.to_digit(10).filter(|&n| n <= 8).map(|n| n as usize))
+        .collect()
+}
+
+fn get_room_rules(matches: &ArgMatches) -> Result<RoomRules, String> {
+    let mut rules = RoomRules::default();
+    if let Some(width) = matches.value_of("room_width") {
+        rules.width = parse_size_range(width, "room-width")?;
+    }
+    if let Some(height) = matches.value_of("room_height") {
+        rules.height = parse_size_range(height, "room-height")?;
+    }
+    if let Some(max_rooms) = matches.value_of("max_rooms") {
+        rules.max_rooms = max_rooms
+            .parse()
+            .map_err(|_| String::from("The --max-rooms parameter must be a positive integer"))?;
+    }
+    Ok(rules)
+}
+
+//a positive number or a range of them like 4-10
+fn parse_size_range(value: &str, option: &str) -> Result<RangeInclusive<usize>, String> {
+    let mut bounds = value.split('-').map(|bound| bound.trim().parse::<usize>());
+    let range = match (bounds.next(), bounds.next(), bounds.next()) {
+        (Some(Ok(size)), None, None) => size..=size,
+        (Some(Ok(min)), Some(Ok(max)), None) => min..=max,
+        _ => 0..=0,
     };
+    if *range.start() == 0 || range.is_empty() {
+        return Err(format!(
+            "The --{} parameter must be a positive integer or a range of them, like 4-10",
+            option
+        ));
+    }
+    Ok(range)
+}
+
+fn get_braid(matches: &ArgMatches) -> Result<f32, String> {
+    let braid = matches.value_of("braid").unwrap_or("0");
+    match braid.parse() {
+        Ok(num) if (0.0..=1.0).contains(&num) => Ok(num),
+        _ => Err(String::from(
+            "The --braid parameter must be a number between 0 and 1",
+        )),
+    }
+}
 
-    wall_percentage
+fn get_seed(matches: &ArgMatches) -> Result<Option<u64>, String> {
+    match matches.value_of("seed").map(str::parse) {
+        None => Ok(None),
+        Some(Ok(seed)) => Ok(Some(seed)),
+        Some(Err(_)) => Err(String::from(
+            "The --seed parameter must be a positive integer",
+        )),
+    }
+}
+
+fn get_delay(matches: &ArgMatches) -> Result<Duration, String> {
+    let delay = matches.value_of("delay").unwrap_or("0");
+    match delay.parse() {
+        Ok(num) => Ok(Duration::from_millis(num)),
+        Err(_) => Err(String::from(
+            "The --delay parameter must be a positive integer",
+        )),
+    }
 }
 
 fn get_heuristic(matches: &ArgMatches) -> Heuristic {
@@ -175,5 +398,19 @@ fn get_heuristic(matches: &ArgMatches) -> Heuristic {
     if matches.is_present("chebyshev") {
         return Heuristic::Chebyshev;
     }
+    if matches.is_present("octile") {
+        return Heuristic::Octile;
+    }
+    if matches.is_present("dijkstra") {
+        return Heuristic::Dijkstra;
+    }
     Heuristic::Manhattan
 }
+
+fn get_movement(matches: &ArgMatches) -> Movement {
+    match matches.value_of("movement") {
+        Some("8") => Movement::EightConnected,
+        Some("8-safe") => Movement::EightConnectedNoCornerCutting,
+        _ => Movement::FourConnected,
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{get_grid_from_file, get_heuristic, get_movement};
+use clap::ArgMatches;
+use grid::{Grid, SearchResult};
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+//searches a path in the map without touching the terminal, so it works in pipes and without a tty
+pub fn solve(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let path = matches.value_of("map").unwrap();
+    let mut grid = get_grid_from_file(path)?;
+    grid.set_movement(get_movement(matches));
+    let heuristic = get_heuristic(matches);
+    let car = get_position(matches, &grid, "start", grid.get_car())?.ok_or_else(|| {
+        format!(
+            "The map {} has no car, its start must be given with --start",
+            path
+        )
+    })?;
+    let goal = get_position(matches, &grid, "goal", grid.get_goal())?
+        .ok_or_else(|| format!("The map {} has no goal, it must be given with --goal", path))?;
+    let time = Instant::now();
+    let result = match matches.value_of("algorithm") {
+        Some("jps") => grid.jump_point_search(car, goal, &heuristic),
+        Some("bidirectional") => grid.bidirectional_search(car, goal, &heuristic),
+        _ => grid.search(car, goal, &heuristic),
+    };
+    let time_used = time.elapsed();
+    if matches.is_present("json") {
+        println!("{}", to_json(&result, time_used));
+    } else {
+        print!("{}", to_text(&result, time_used));
+    }
+    Ok(())
+}
+
+//the position given in the option as its row and column, which replaces the one in the map
+//it's returned as its x and y, like the grid uses them
+fn get_position(
+    matches: &ArgMatches,
+    grid: &Grid,
+    option: &str,
+    in_map: Option<(usize, usize)>,
+) -> Result<Option<(usize, usize)>, String> {
+    let value = match matches.value_of(option) {
+        None => return Ok(in_map),
+        Some(value) => value,
+    };
+    let mut coordinates = value.split(',').map(|coordinate| coordinate.trim().parse());
+    let pos = match (coordinates.next(), coordinates.next(), coordinates.next()) {
+        (Some(Ok(row)), Some(Ok(column)), None) => (column, row),
+        _ => {
+            return Err(format!(
+                "The --{} parameter must be a row and a column separated by a comma, like 3,7",
+                option
+            ))
+        }
+    };
+    if !grid.is_inside(pos) {
+        return Err(format!(
+            "The --{} position {} is outside the map, its rows go from 0 to {} and its columns from 0 to {}",
+            option,
+            value,
+            grid.m() - 1,
+            grid.n() - 1
+        ));
+    }
+    if !grid.is_traversable(pos) {
+        return Err(format!("The --{} position {} is a wall", option, value));
+    }
+    Ok(Some(pos))
+}
+
+//the positions are written as (row, column), like --start and --goal take them
+fn to_text(result: &SearchResult, time_used: Duration) -> String {
+    let mut text = match result.get_path() {
+        None => String::from("Path: none\n"),
+        Some(path) => format!(
+            "Path (row, column): {}\nLength: {}\nCost: {}\n",
+            path.get_path()
+                .iter()
+                .map(|(x, y)| format!("({}, {})", y, x))
+                .collect::<Vec<_>>()
+                .join(" "),
+            path.get_path_length(),
+            path.get_cost()
+        ),
+    };
+    text += &format!(
+        "Explored: {}\nTime: {} μs\n",
+        result.get_n_explored(),
+        time_used.as_micros()
+    );
+    text
+}
+
+//the path, the length and the cost are null if there isn't a path
+//the positions of the path are objects with their row and column
+fn to_json(result: &SearchResult, time_used: Duration) -> String {
+    let (path, length, cost) = match result.get_path() {
+        None => (
+            String::from("null"),
+            String::from("null"),
+            String::from("null"),
+        ),
+        Some(path) => (
+            format!(
+                "[{}]",
+                path.get_path()
+                    .iter()
+                    .map(|(x, y)| format!("{{\"row\": {}, \"column\": {}}}", y, x))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            path.get_path_length().to_string(),
+            path.get_cost().to_string(),
+        ),
+    };
+    format!(
+        "{{\"path\": {}, \"length\": {}, \"cost\": {}, \"explored\": {}, \"time_us\": {}}}",
+        path,
+        length,
+        cost,
+        result.get_n_explored(),
+        time_used.as_micros()
+    )
+}
@@ -0,0 +1,154 @@
+use super::content::{Content, Direction};
+use super::path_finding::{DStarLite, Movement};
+use super::{Grid, Heuristic};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AgentStatus {
+    Moving,
+    //a wall found by the sensors blocked the plan, so a new one was made
+    Replanned,
+    Arrived,
+    //the walls found so far leave no path to the goal, so there isn't any in the real map either
+    Stuck,
+}
+
+//a car that only knows the walls its sensors have found
+//it plans as if the cells it hasn't sensed were free and replans when a wall blocks its plan
+//it only moves in the 4 directions, as the sensors can't see the diagonal neighbours
+pub struct Agent<H: Heuristic> {
+    //the map as the agent believes it is, with the walls sensed so far
+    belief: Grid,
+    planner: DStarLite<H>,
+    //the cells left to reach the goal, without the position of the agent
+    plan: Vec<(usize, usize)>,
+    //the walls sensed since the last plan was made
+    new_walls: Vec<(usize, usize)>,
+    trajectory: Vec<(usize, usize)>,
+    distance: f32,
+    n_replans: usize,
+    status: AgentStatus,
+}
+
+impl<H: Heuristic> Agent<H> {
+    //the agent starts in the car of the map with its first plan already made
+    pub fn new(world: &Grid, heuristic: H) -> Self {
+        let (car, goal) = (
+            world.car.expect("The map doesn't have a car"),
+            world.goal.expect("The map doesn't have a goal"),
+        );
+        let mut belief = Grid::new(world.m(), world.n());
+        belief.set_movement(Movement::FourConnected);
+        belief.set_goal(goal.0, goal.1);
+        belief.set_car(car.0, car.1);
+        let planner = DStarLite::new(&belief, car, goal, heuristic);
+        let mut agent = Agent {
+            belief,
+            planner,
+            plan: Vec::new(),
+            new_walls: Vec::new(),
+            trajectory: vec![car],
+            distance: 0.0,
+            n_replans: 0,
+            status: AgentStatus::Moving,
+        };
+        agent.sense(world);
+        agent.replan();
+        agent
+    }
+
+    pub fn get_belief(&self) -> &Grid {
+        &self.belief
+    }
+
+    pub fn get_position(&self) -> (usize, usize) {
+        *self.trajectory.last().unwrap()
+    }
+
+    pub fn get_plan(&self) -> &[(usize, usize)] {
+        &self.plan
+    }
+
+    //the positions the agent has been in, from the car to its current position
+    pub fn get_trajectory(&self) -> &[(usize, usize)] {
+        &self.trajectory
+    }
+
+    //the cost of the moves made so far in the real map
+    pub fn get_distance(&self) -> f32 {
+        self.distance
+    }
+
+    pub fn get_n_replans(&self) -> usize {
+        self.n_replans
+    }
+
+    pub fn get_status(&self) -> AgentStatus {
+        self.status
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self.status, AgentStatus::Arrived | AgentStatus::Stuck)
+    }
+
+    //moves the agent a cell along its plan, senses the new neighbours and replans if needed
+    pub fn step(&mut self, world: &Grid) -> AgentStatus {
+        assert!(world.m() == self.belief.m() && world.n() == self.belief.n());
+        if self.is_finished() {
+            return self.status;
+        }
+        let pos = self.get_position();
+        let next = self.plan.remove(0);
+        //the next cell is always a neighbour that was already sensed
+        self.distance += world.get_move_cost(pos, next);
+        self.trajectory.push(next);
+        if next == self.belief.goal.unwrap() {
+            self.status = AgentStatus::Arrived;
+            return self.status;
+        }
+        self.belief.set_car(next.0, next.1);
+        self.sense(world);
+        self.status = if self.plan.iter().any(|cell| self.new_walls.contains(cell)) {
+            self.n_replans += 1;
+            self.replan();
+            if self.status == AgentStatus::Stuck {
+                return self.status;
+            }
+            AgentStatus::Replanned
+        } else {
+            AgentStatus::Moving
+        };
+        self.status
+    }
+
+    //adds the walls around the agent to the belief map
+    fn sense(&mut self, world: &Grid) {
+        let pos = self.get_position();
+        let perception = world.get_perception_at(pos);
+        for &dir in &Direction::ORTHOGONAL {
+            match dir.apply(pos) {
+                Some(neigh)
+                    if perception.is_occupied(dir) == Some(true)
+                        && self.belief.is_inside(neigh)
+                        && !matches!(self.belief.grid[neigh.1][neigh.0], Content::Wall) =>
+                {
+                    self.belief.set_wall(neigh.0, neigh.1);
+                    self.new_walls.push(neigh);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn replan(&mut self) {
+        self.planner.move_start(self.get_position());
+        self.planner.update_cells(&self.belief, &self.new_walls);
+        self.new_walls.clear();
+        match self.planner.plan(&self.belief).into_path() {
+            Some(path) => self.plan = path.get_path()[1..].to_vec(),
+            None => {
+                self.plan.clear();
+                self.status = AgentStatus::Stuck;
+            }
+        }
+    }
+}
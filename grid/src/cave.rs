@@ -0,0 +1,143 @@
+use super::content::Content;
+use super::{get_seeded_rng, Grid};
+use rand::{thread_rng, Rng};
+
+//the rules of the cellular automaton that turns random walls into caves
+//the neighbours of a cell are the 8 cells around it and the ones outside the grid count as walls
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CaveRules {
+    //the percentage of walls before the first iteration
+    pub wall_percentage: usize,
+    //an empty cell becomes a wall if the number of walls around it is one of these
+    pub birth: Vec<usize>,
+    //a wall stays if the number of walls around it is one of these
+    pub survival: Vec<usize>,
+    pub iterations: usize,
+}
+
+impl Default for CaveRules {
+    //B5678/S45678, which gives big open caves
+    fn default() -> Self {
+        CaveRules {
+            wall_percentage: 45,
+            birth: vec![5, 6, 7, 8],
+            survival: vec![4, 5, 6, 7, 8],
+            iterations: 5,
+        }
+    }
+}
+
+impl Grid {
+    pub fn fill_cave(&mut self, rules: &CaveRules) {
+        self.fill_cave_with_rng(rules, &mut thread_rng());
+    }
+
+    pub fn fill_cave_with_seed(&mut self, rules: &CaveRules, seed: u64) {
+        self.fill_cave_with_rng(rules, &mut get_seeded_rng(seed));
+    }
+
+    //the car and the goal are placed in two random cells of the same cave
+    pub fn fill_cave_with_rng<R: Rng + ?Sized>(&mut self, rules: &CaveRules, rng: &mut R) {
+        assert!(rules.wall_percentage <= 100);
+        assert!(rules
+            .birth
+            .iter()
+            .chain(rules.survival.iter())
+            .all(|&n| n <= 8));
+        self.car = None;
+        self.goal = None;
+        self.clear_path();
+        let mut walls: Vec<Vec<bool>> = (0..self.m())
+            .map(|_| {
+                (0..self.n())
+                    .map(|_| rng.gen_range(1, 101) <= rules.wall_percentage)
+                    .collect()
+            })
+            .collect();
+        for _ in 0..rules.iterations {
+            walls = (0..self.m())
+                .map(|y| {
+                    (0..self.n())
+                        .map(|x| {
+                            let n_walls = count_walls_around(&walls, (x, y));
+                            if walls[y][x] {
+                                rules.survival.contains(&n_walls)
+                            } else {
+                                rules.birth.contains(&n_walls)
+                            }
+                        })
+                        .collect()
+                })
+                .collect();
+        }
+        for (row, walls_row) in self.grid.iter_mut().zip(walls.iter()) {
+            for (cell, &is_wall) in row.iter_mut().zip(walls_row.iter()) {
+                *cell = if is_wall {
+                    Content::Wall
+                } else {
+                    Content::Empty
+                };
+            }
+        }
+        self.place_connected_car_and_goal(rng);
+    }
+}
+
+fn count_walls_around(walls: &[Vec<bool>], pos: (usize, usize)) -> usize {
+    let mut count = 0;
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            if dx == 0 && dy == 0 {
+                continue;
+            }
+            let x = pos.0 as isize + dx;
+            let y = pos.1 as isize + dy;
+            let is_outside =
+                x < 0 || y < 0 || y as usize >= walls.len() || x as usize >= walls[0].len();
+            if is_outside || walls[y as usize][x as usize] {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CaveRules;
+    use crate::Grid;
+
+    #[test]
+    fn the_car_and_the_goal_are_in_the_same_cave() {
+        for seed in 0..50 {
+            let mut grid = Grid::new(20, 30);
+            grid.fill_cave_with_seed(&CaveRules::default(), seed);
+            let (car, goal) = (grid.get_car().unwrap(), grid.get_goal().unwrap());
+            assert!(grid.is_connected(car, goal), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn a_seed_always_generates_the_same_cave() {
+        let mut grid = Grid::new(20, 30);
+        grid.fill_cave_with_seed(&CaveRules::default(), 7);
+        let mut other = Grid::new(20, 30);
+        other.fill_cave_with_seed(&CaveRules::default(), 7);
+        assert_eq!(grid.to_map_string(), other.to_map_string());
+        other.fill_cave_with_seed(&CaveRules::default(), 8);
+        assert_ne!(grid.to_map_string(), other.to_map_string());
+    }
+
+    #[test]
+    fn the_walls_without_survival_rules_disappear() {
+        let rules = CaveRules {
+            wall_percentage: 100,
+            birth: Vec::new(),
+            survival: Vec::new(),
+            iterations: 1,
+        };
+        let mut grid = Grid::new(4, 5);
+        grid.fill_cave_with_seed(&rules, 0);
+        assert_eq!(grid.get_reachable_cells(grid.get_car().unwrap()).len(), 20);
+    }
+}
@@ -1,38 +1,105 @@
-use crossterm::style::Colorize;
-use std::fmt;
+pub const MAX_WEIGHT: u8 = 9;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Content {
     Car,
     Goal,
     Wall,
     Empty,
+    //a cell that costs more than one to go through, like mud or gravel
+    Weighted(u8),
     Trace(Direction),
     Explored,
+    //explored by the part of a search that goes from the goal to the car
+    ExploredBackward,
+    //waiting to be expanded by a search that is still running
+    Open,
+    //the cell being expanded by a search that is still running
+    Current,
 }
 
-#[derive(Clone, Copy)]
+impl Content {
+    //the cost of moving into the cell or None if it can't be traversed
+    pub fn get_cost(&self) -> Option<f32> {
+        match self {
+            Content::Wall => None,
+            Content::Weighted(weight) => Some(*weight as f32),
+            _ => Some(1.0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Direction {
     Left,
     Up,
     Right,
     Down,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
 }
 
-impl fmt::Display for Content {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl Direction {
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::Right,
+        Direction::Left,
+        Direction::Down,
+        Direction::Up,
+    ];
+    pub const DIAGONAL: [Direction; 4] = [
+        Direction::DownRight,
+        Direction::UpRight,
+        Direction::DownLeft,
+        Direction::UpLeft,
+    ];
+
+    //returns the direction of the move that goes from one cell to an adjacent one
+    pub fn between(from: (usize, usize), to: (usize, usize)) -> Option<Direction> {
+        Direction::from_delta((
+            to.0 as isize - from.0 as isize,
+            to.1 as isize - from.1 as isize,
+        ))
+    }
+
+    //the direction that changes the coordinates by delta, if there is one
+    pub fn from_delta(delta: (isize, isize)) -> Option<Direction> {
+        Direction::ORTHOGONAL
+            .iter()
+            .chain(Direction::DIAGONAL.iter())
+            .copied()
+            .find(|dir| dir.get_delta() == delta)
+    }
+
+    //the change in the x and y coordinates caused by the move
+    pub fn get_delta(&self) -> (isize, isize) {
         match self {
-            Content::Car => write!(f, "{}", "🚗".on_black()),
-            Content::Goal => write!(f, "{}", "🏁".on_black()),
-            Content::Wall => write!(f, "{}", "  ".on_red()),
-            Content::Empty => write!(f, "{}", "  ".on_black()),
-            Content::Explored => write!(f, "{}", "  ".on_yellow()),
-            Content::Trace(dir) => match dir {
-                Direction::Left => write!(f, "{}", "←←".on_black()),
-                Direction::Up => write!(f, "{}", "↑↑".on_black()),
-                Direction::Right => write!(f, "{}", "→→".on_black()),
-                Direction::Down => write!(f, "{}", "↓↓".on_black()),
-            },
+            Direction::Left => (-1, 0),
+            Direction::Up => (0, -1),
+            Direction::Right => (1, 0),
+            Direction::Down => (0, 1),
+            Direction::UpLeft => (-1, -1),
+            Direction::UpRight => (1, -1),
+            Direction::DownLeft => (-1, 1),
+            Direction::DownRight => (1, 1),
+        }
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        let (dx, dy) = self.get_delta();
+        dx != 0 && dy != 0
+    }
+
+    //the position reached after the move, or None if it would go past the top or left border
+    pub fn apply(&self, pos: (usize, usize)) -> Option<(usize, usize)> {
+        let (dx, dy) = self.get_delta();
+        let x = pos.0 as isize + dx;
+        let y = pos.1 as isize + dy;
+        if x < 0 || y < 0 {
+            None
+        } else {
+            Some((x as usize, y as usize))
         }
     }
 }
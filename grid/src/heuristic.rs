@@ -0,0 +1,74 @@
+use std::cmp::{max, min};
+
+//estimates the distance left from a position to the goal
+//closures with the signature of the heuristic functions can be used directly
+pub trait Heuristic {
+    fn estimate(&self, pos: (usize, usize), goal: (usize, usize)) -> f32;
+}
+
+impl<F> Heuristic for F
+where
+    F: Fn((usize, usize), (usize, usize)) -> f32,
+{
+    fn estimate(&self, pos: (usize, usize), goal: (usize, usize)) -> f32 {
+        self(pos, goal)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Manhattan;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Euclidean;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Chebyshev;
+
+//the exact distance in an empty grid with diagonal moves that cost sqrt(2)
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Octile;
+
+//doesn't guess anything, which turns A* into Dijkstra's algorithm
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Zero;
+
+impl Heuristic for Manhattan {
+    fn estimate(&self, pos: (usize, usize), goal: (usize, usize)) -> f32 {
+        let (dx, dy) = get_deltas(pos, goal);
+        (dx + dy) as f32
+    }
+}
+
+impl Heuristic for Euclidean {
+    fn estimate(&self, pos: (usize, usize), goal: (usize, usize)) -> f32 {
+        let (dx, dy) = get_deltas(pos, goal);
+        ((dx as f32).powi(2) + (dy as f32).powi(2)).sqrt()
+    }
+}
+
+impl Heuristic for Chebyshev {
+    fn estimate(&self, pos: (usize, usize), goal: (usize, usize)) -> f32 {
+        let (dx, dy) = get_deltas(pos, goal);
+        max(dx, dy) as f32
+    }
+}
+
+impl Heuristic for Octile {
+    fn estimate(&self, pos: (usize, usize), goal: (usize, usize)) -> f32 {
+        let (dx, dy) = get_deltas(pos, goal);
+        max(dx, dy) as f32 + (std::f32::consts::SQRT_2 - 1.0) * min(dx, dy) as f32
+    }
+}
+
+impl Heuristic for Zero {
+    fn estimate(&self, _pos: (usize, usize), _goal: (usize, usize)) -> f32 {
+        0.0
+    }
+}
+
+fn get_deltas(pos1: (usize, usize), pos2: (usize, usize)) -> (usize, usize) {
+    (
+        max(pos1.0, pos2.0) - min(pos1.0, pos2.0),
+        max(pos1.1, pos2.1) - min(pos1.1, pos2.1),
+    )
+}
@@ -1,31 +1,70 @@
-use crossterm::style::Colorize;
+use crossterm::style::style;
 use std::fmt;
+mod agent;
+mod cave;
 mod content;
+pub mod heuristic;
+mod map;
+mod maze;
+mod moving_ai;
 mod path_finding;
+mod perception;
+mod rooms;
+mod theme;
+mod viewport;
+pub use agent::{Agent, AgentStatus};
+pub use cave::CaveRules;
 use content::Content;
+pub use content::{Direction, MAX_WEIGHT};
+pub use heuristic::Heuristic;
+pub use map::MapError;
+pub use maze::MazeAlgorithm;
+pub use path_finding::{AStarSearch, DStarLite, Movement, PathResult, SearchResult};
+pub use perception::Perception;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
 use rand::{thread_rng, Rng};
+use rand_chacha::ChaCha8Rng;
+pub use rooms::RoomRules;
+pub use theme::Theme;
+pub use viewport::Viewport;
 
+//the maps generated by the solvable generation before giving up
+pub const MAX_ATTEMPTS: usize = 10_000;
+
+#[derive(Clone)]
 pub struct Grid {
     grid: Vec<Vec<Content>>,
+    //what was drawn by a search on top of each cell, so the cells themselves aren't lost
+    marks: Vec<Vec<Option<Content>>>,
     goal: Option<(usize, usize)>,
     car: Option<(usize, usize)>,
+    movement: Movement,
+    //the text after the cells of each row and the lines after the last row in a map file
+    row_comments: Vec<String>,
+    footer_comments: Vec<String>,
+    //what the cell of the car is when the car isn't there
+    under_car: Content,
 }
 
+//the grid with the default theme
 impl fmt::Display for Grid {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let theme = Theme::default();
+        let border = style("  ").on(theme.border);
         for _ in 0..self.grid[0].len() + 2 {
-            write!(f, "{}", "  ".on_dark_blue())?;
+            write!(f, "{}", border)?;
         }
         write!(f, "\n\r")?;
-        for row in self.grid.iter() {
-            write!(f, "{}", "  ".on_dark_blue())?;
-            for cell in row.iter() {
-                write!(f, "{}", cell)?
+        for (row, marks_row) in self.grid.iter().zip(self.marks.iter()) {
+            write!(f, "{}", border)?;
+            for (cell, mark) in row.iter().zip(marks_row.iter()) {
+                write!(f, "{}", theme.draw(mark.unwrap_or(*cell)))?
             }
-            write!(f, "{}", "  \n\r".on_dark_blue())?;
+            write!(f, "{}\n\r", border)?;
         }
         for _ in 0..self.grid[0].len() + 2 {
-            write!(f, "{}", "  ".on_dark_blue())?;
+            write!(f, "{}", border)?;
         }
         write!(f, "\n\r")?;
         Ok(())
@@ -37,8 +76,13 @@ impl Grid {
         assert!(m != 0 && n != 0);
         Grid {
             grid: vec![vec![Content::Empty; n]; m],
+            marks: vec![vec![None; n]; m],
             goal: None,
             car: None,
+            movement: Movement::default(),
+            row_comments: vec![String::new(); m],
+            footer_comments: Vec::new(),
+            under_car: Content::Empty,
         }
     }
 
@@ -51,47 +95,65 @@ impl Grid {
     }
 
     pub fn has_goal(&self) -> bool {
-        match self.goal {
-            None => false,
-            Some(_) => true,
-        }
+        self.goal.is_some()
     }
 
     pub fn has_car(&self) -> bool {
-        match self.car {
-            None => false,
-            Some(_) => true,
-        }
+        self.car.is_some()
+    }
+
+    pub fn get_car(&self) -> Option<(usize, usize)> {
+        self.car
+    }
+
+    pub fn get_goal(&self) -> Option<(usize, usize)> {
+        self.goal
+    }
+
+    pub fn get_movement(&self) -> Movement {
+        self.movement
+    }
+
+    pub fn set_movement(&mut self, movement: Movement) {
+        self.movement = movement;
+    }
+
+    pub fn is_inside(&self, pos: (usize, usize)) -> bool {
+        pos.0 < self.n() && pos.1 < self.m()
     }
 
     pub fn set_width(&mut self, n: usize) {
         assert!(n != 0);
         let width = self.grid[0].len();
-        if n == width {
-            return;
-        } else if n < width {
+        if n < width {
             self.grid.iter_mut().for_each(|row| row.truncate(n));
+            self.marks.iter_mut().for_each(|row| row.truncate(n));
             self.check_car_valididy();
             self.check_goal_valididy();
-        } else {
+        } else if n > width {
             self.grid
                 .iter_mut()
                 .for_each(|row| (0..n - width).for_each(|_| row.push(Content::Empty)));
+            self.marks
+                .iter_mut()
+                .for_each(|row| (0..n - width).for_each(|_| row.push(None)));
         }
     }
 
     pub fn set_height(&mut self, m: usize) {
         assert!(m != 0);
         let height = self.grid.len();
-        if m == height {
-            return;
-        } else if m < height {
+        if m < height {
             self.grid.truncate(m);
+            self.marks.truncate(m);
+            self.row_comments.truncate(m);
             self.check_car_valididy();
             self.check_goal_valididy();
-        } else {
+        } else if m > height {
             let width = self.grid[0].len();
             (0..m - height).for_each(|_| self.grid.push(vec![Content::Empty; width]));
+            (0..m - height).for_each(|_| self.marks.push(vec![None; width]));
+            self.row_comments.resize(m, String::new());
         }
     }
 
@@ -102,6 +164,22 @@ impl Grid {
             _ => {}
         }
         self.grid[y][x] = Content::Wall;
+        self.marks[y][x] = None;
+    }
+
+    pub fn set_weighted(&mut self, x: usize, y: usize, weight: u8) {
+        assert!(weight != 0 && weight <= MAX_WEIGHT);
+        if weight == 1 {
+            self.set_empty(x, y);
+            return;
+        }
+        match &mut self.grid[y][x] {
+            Content::Car => self.car = None,
+            Content::Goal => self.goal = None,
+            _ => {}
+        }
+        self.grid[y][x] = Content::Weighted(weight);
+        self.marks[y][x] = None;
     }
 
     pub fn set_goal(&mut self, x: usize, y: usize) {
@@ -111,6 +189,7 @@ impl Grid {
             _ => {}
         }
         self.grid[y][x] = Content::Goal;
+        self.marks[y][x] = None;
         if let Some(old_goal_pos) = &mut self.goal {
             self.grid[old_goal_pos.1][old_goal_pos.0] = Content::Empty;
         }
@@ -123,11 +202,27 @@ impl Grid {
             Content::Car => return,
             _ => {}
         }
+        //the cost of the cell is given back when the car leaves it
+        let terrain = match self.grid[y][x] {
+            Content::Weighted(weight) => Content::Weighted(weight),
+            _ => Content::Empty,
+        };
         self.grid[y][x] = Content::Car;
+        self.marks[y][x] = None;
         if let Some(old_car_pos) = &mut self.car {
-            self.grid[old_car_pos.1][old_car_pos.0] = Content::Empty;
+            self.grid[old_car_pos.1][old_car_pos.0] = self.under_car;
         }
         self.car = Some((x, y));
+        self.under_car = terrain;
+    }
+
+    //the cell without the car, so the cost of the cell the car is on isn't hidden by it
+    pub(crate) fn get_terrain(&self, pos: (usize, usize)) -> Content {
+        if self.car == Some(pos) {
+            self.under_car
+        } else {
+            self.grid[pos.1][pos.0]
+        }
     }
 
     pub fn set_empty(&mut self, x: usize, y: usize) {
@@ -137,23 +232,37 @@ impl Grid {
             _ => {}
         }
         self.grid[y][x] = Content::Empty;
+        self.marks[y][x] = None;
     }
 
     pub fn clear(&mut self) {
         self.grid = vec![vec![Content::Empty; self.grid[0].len()]; self.grid.len()];
+        self.car = None;
+        self.goal = None;
+        self.clear_path();
     }
 
     pub fn fill_random(&mut self, wall_percentage: usize) {
+        self.fill_random_with_rng(wall_percentage, &mut thread_rng());
+    }
+
+    //the same seed always generates the same map for the same size and percentage of walls
+    pub fn fill_random_with_seed(&mut self, wall_percentage: usize, seed: u64) {
+        self.fill_random_with_rng(wall_percentage, &mut get_seeded_rng(seed));
+    }
+
+    pub fn fill_random_with_rng<R: Rng + ?Sized>(&mut self, wall_percentage: usize, rng: &mut R) {
         assert!(wall_percentage <= 100);
         self.car = None;
         self.goal = None;
-        self.fill_random_walls(wall_percentage);
-        let car_pos = self.get_random_pos();
+        self.clear_path();
+        self.fill_random_walls(wall_percentage, rng);
+        let car_pos = self.get_random_pos(rng);
         self.set_car(car_pos.0, car_pos.1);
         //if the grid only has one cell we won't place the goal
         if self.m() * self.n() != 1 {
             let goal_pos = loop {
-                let pos = self.get_random_pos();
+                let pos = self.get_random_pos(rng);
                 if pos != car_pos {
                     break pos;
                 }
@@ -162,9 +271,40 @@ impl Grid {
         }
     }
 
-    fn fill_random_walls(&mut self, wall_percentage: usize) {
-        let mut rng = thread_rng();
-        for content in self.grid.iter_mut().map(|i| i.iter_mut()).flatten() {
+    pub fn fill_random_solvable(&mut self, wall_percentage: usize) -> Option<usize> {
+        self.fill_random_solvable_with_rng(wall_percentage, &mut thread_rng())
+    }
+
+    pub fn fill_random_solvable_with_seed(
+        &mut self,
+        wall_percentage: usize,
+        seed: u64,
+    ) -> Option<usize> {
+        self.fill_random_solvable_with_rng(wall_percentage, &mut get_seeded_rng(seed))
+    }
+
+    //like fill_random, but the maps without a path from the car to the goal are generated again
+    //returns the number of maps generated, or None if none of the first MAX_ATTEMPTS had a path
+    pub fn fill_random_solvable_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        wall_percentage: usize,
+        rng: &mut R,
+    ) -> Option<usize> {
+        assert!(
+            self.m() * self.n() > 1,
+            "The car and the goal need two cells"
+        );
+        for attempt in 1..=MAX_ATTEMPTS {
+            self.fill_random_with_rng(wall_percentage, rng);
+            if self.is_connected(self.car.unwrap(), self.goal.unwrap()) {
+                return Some(attempt);
+            }
+        }
+        None
+    }
+
+    fn fill_random_walls<R: Rng + ?Sized>(&mut self, wall_percentage: usize, rng: &mut R) {
+        for content in self.grid.iter_mut().flat_map(|i| i.iter_mut()) {
             if rng.gen_range(1, 101) <= wall_percentage {
                 *content = Content::Wall;
             } else {
@@ -173,14 +313,39 @@ impl Grid {
         }
     }
 
-    fn get_random_pos(&mut self) -> (usize, usize) {
+    fn get_random_pos<R: Rng + ?Sized>(&mut self, rng: &mut R) -> (usize, usize) {
         let n_cells = self.m() * self.n();
-        let pos = thread_rng().gen_range(0, n_cells);
+        let pos = rng.gen_range(0, n_cells);
         let y = pos / self.n();
         let x = pos % self.n();
         (x, y)
     }
 
+    //places the car in a random traversable cell and the goal in another one reachable from it
+    //the cells without any other reachable one are skipped
+    fn place_connected_car_and_goal<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.car = None;
+        self.goal = None;
+        let mut candidates: Vec<(usize, usize)> = (0..self.m())
+            .flat_map(|y| (0..self.n()).map(move |x| (x, y)))
+            .filter(|&pos| self.is_traversable(pos))
+            .collect();
+        candidates.shuffle(rng);
+        for &car_pos in &candidates {
+            let reachable = self.get_reachable_cells(car_pos);
+            if reachable.len() > 1 {
+                let goal_pos = *reachable[1..].choose(rng).unwrap();
+                self.set_car(car_pos.0, car_pos.1);
+                self.set_goal(goal_pos.0, goal_pos.1);
+                return;
+            }
+        }
+        //there are no connected cells, so at least the car is placed
+        if let Some(&car_pos) = candidates.first() {
+            self.set_car(car_pos.0, car_pos.1);
+        }
+    }
+
     fn check_car_valididy(&mut self) {
         if let Some(pos) = self.car {
             if pos.0 >= self.n() || pos.1 >= self.m() {
@@ -197,3 +362,72 @@ impl Grid {
         }
     }
 }
+
+//the generator of the maps made from a seed. Its algorithm is fixed, unlike the one of StdRng,
+//so a seed keeps generating the same map when rand is updated
+pub(crate) fn get_seeded_rng(seed: u64) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use crate::heuristic::Manhattan;
+
+    //if this changes, the seeds recorded before don't give the same maps anymore
+    #[test]
+    fn a_seed_always_generates_the_same_map() {
+        let mut grid = Grid::new(4, 6);
+        grid.fill_random_with_seed(40, 42);
+        assert_eq!(
+            grid.to_map_string(),
+            "4\n6\n C    \n     X\n XXXX \n  XG  \n"
+        );
+    }
+
+    #[test]
+    fn the_car_keeps_the_cost_of_its_cell() {
+        let mut grid = Grid::new(1, 3);
+        grid.set_weighted(1, 0, 5);
+        grid.set_car(1, 0);
+        assert_eq!(grid.get_move_cost((0, 0), (1, 0)), 5.0);
+        let path = grid.search((0, 0), (2, 0), &Manhattan).into_path().unwrap();
+        assert_eq!(path.get_cost(), 6.0);
+        let path = grid
+            .jump_point_search((0, 0), (2, 0), &Manhattan)
+            .into_path()
+            .unwrap();
+        assert_eq!(path.get_cost(), 6.0);
+        //and gives it back when it leaves
+        grid.set_car(0, 0);
+        assert_eq!(grid.get_move_cost((0, 0), (1, 0)), 5.0);
+    }
+
+    //a path that turns around a wall, so the arrows at the turns are checked
+    fn get_grid_with_turns() -> Grid {
+        let mut grid = Grid::new(5, 6);
+        grid.set_car(0, 4);
+        grid.set_goal(5, 0);
+        for y in 1..5 {
+            grid.set_wall(2, y);
+        }
+        grid.set_wall(4, 0);
+        grid
+    }
+
+    #[test]
+    fn restarting_a_drive_draws_the_same_trace() {
+        let mut grid = get_grid_with_turns();
+        let result = grid.search((0, 4), (5, 0), &Manhattan);
+        let path = result.get_path().unwrap();
+        grid.draw_path(path);
+        let path = path.get_path();
+        let drawn = grid.marks.clone();
+        for &(x, y) in &path[1..path.len() - 1] {
+            grid.set_car(x, y);
+        }
+        grid.set_car(path[0].0, path[0].1);
+        grid.draw_trajectory(path);
+        assert_eq!(grid.marks, drawn);
+    }
+}
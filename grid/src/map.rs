@@ -0,0 +1,219 @@
+use super::content::Content;
+use super::Grid;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+//a problem found while reading a map, the line and the column start at 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapError {
+    line: usize,
+    column: usize,
+    expected: String,
+    found: String,
+}
+
+impl MapError {
+    pub(crate) fn new(line: usize, column: usize, expected: &str, found: &str) -> Self {
+        MapError {
+            line,
+            column,
+            expected: expected.to_string(),
+            found: found.to_string(),
+        }
+    }
+
+    pub fn get_line(&self) -> usize {
+        self.line
+    }
+
+    pub fn get_column(&self) -> usize {
+        self.column
+    }
+
+    pub fn get_expected(&self) -> &str {
+        &self.expected
+    }
+
+    pub fn get_found(&self) -> &str {
+        &self.found
+    }
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, found {}",
+            self.line, self.column, self.expected, self.found
+        )
+    }
+}
+
+impl Error for MapError {}
+
+//the map has the number of rows in the first line, the number of columns in the second
+//and then a line for every row where C is the car, G the goal, X a wall and 1 to 9 the cost of the cell
+//any other character is an empty cell
+//the characters after the last column and the lines after the last row are kept as comments
+impl FromStr for Grid {
+    type Err = MapError;
+
+    fn from_str(map: &str) -> Result<Self, Self::Err> {
+        let mut lines = map.lines();
+        let m = parse_size(lines.next(), 1, "rows")?;
+        let n = parse_size(lines.next(), 2, "columns")?;
+        let mut grid = Grid::new(m, n);
+        for i in 0..m {
+            let line_number = i + 3;
+            let row = lines.next().ok_or_else(|| {
+                MapError::new(
+                    line_number,
+                    1,
+                    &format!("row {} of {}", i + 1, m),
+                    "end of file",
+                )
+            })?;
+            let mut row_chars = row.chars();
+            for j in 0..n {
+                let c = row_chars.next().ok_or_else(|| {
+                    MapError::new(
+                        line_number,
+                        j + 1,
+                        &format!("{} cells in the row", n),
+                        &j.to_string(),
+                    )
+                })?;
+                match c {
+                    'C' => grid.set_car(j, i),
+                    'G' => grid.set_goal(j, i),
+                    'X' => grid.set_wall(j, i),
+                    '1'..='9' => grid.set_weighted(j, i, c.to_digit(10).unwrap() as u8),
+                    _ => {}
+                }
+            }
+            grid.row_comments[i] = row_chars.collect();
+        }
+        grid.footer_comments = lines.map(String::from).collect();
+        Ok(grid)
+    }
+}
+
+impl Grid {
+    //writes the grid in the same format it's read from, with the empty cells as spaces
+    //the search drawn on it isn't saved, and neither is the cost of the cell under the car,
+    //as the format has no way to represent both
+    //reading what it writes gives the same grid and the same text, but a file read with other
+    //characters for the empty cells, cells of cost 1 or \r\n line endings is written differently
+    pub fn to_map_string(&self) -> String {
+        let mut map = format!("{}\n{}\n", self.m(), self.n());
+        for (row, comment) in self.grid.iter().zip(self.row_comments.iter()) {
+            for cell in row {
+                map.push(match cell {
+                    Content::Car => 'C',
+                    Content::Goal => 'G',
+                    Content::Wall => 'X',
+                    Content::Weighted(weight) => (b'0' + weight) as char,
+                    _ => ' ',
+                });
+            }
+            map.push_str(comment);
+            map.push('\n');
+        }
+        for comment in &self.footer_comments {
+            map.push_str(comment);
+            map.push('\n');
+        }
+        map
+    }
+}
+
+fn parse_size(line: Option<&str>, line_number: usize, name: &str) -> Result<usize, MapError> {
+    let expected = format!("the number of {}", name);
+    let line = line.ok_or_else(|| MapError::new(line_number, 1, &expected, "end of file"))?;
+    match line.trim().parse() {
+        Ok(0) => Err(MapError::new(
+            line_number,
+            1,
+            &format!("a positive number of {}", name),
+            "0",
+        )),
+        Ok(size) => Ok(size),
+        Err(_) => Err(MapError::new(
+            line_number,
+            1,
+            &expected,
+            &format!("\"{}\"", line),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MapError;
+    use crate::Grid;
+
+    fn parse_error(map: &str) -> MapError {
+        match map.parse::<Grid>() {
+            Ok(_) => panic!("The map should be invalid"),
+            Err(err) => err,
+        }
+    }
+
+    #[test]
+    fn a_missing_header_is_reported_in_its_line() {
+        assert_eq!(
+            parse_error(""),
+            MapError::new(1, 1, "the number of rows", "end of file")
+        );
+        assert_eq!(
+            parse_error("3\n"),
+            MapError::new(2, 1, "the number of columns", "end of file")
+        );
+        assert_eq!(
+            parse_error("3\nfour\n"),
+            MapError::new(2, 1, "the number of columns", "\"four\"")
+        );
+    }
+
+    #[test]
+    fn a_ragged_row_is_reported_where_it_ends() {
+        let err = parse_error("2\n4\nC  G\nX\n");
+        assert_eq!(err, MapError::new(4, 2, "4 cells in the row", "1"));
+        assert_eq!(
+            err.to_string(),
+            "line 4, column 2: expected 4 cells in the row, found 1"
+        );
+    }
+
+    #[test]
+    fn a_missing_row_is_reported_after_the_last_one() {
+        assert_eq!(
+            parse_error("3\n2\nCG\n  \n"),
+            MapError::new(5, 1, "row 3 of 3", "end of file")
+        );
+    }
+
+    #[test]
+    fn a_map_is_written_as_it_was_read() {
+        let map = "3\n5\nC 9 X this row has a comment\n 2X  \nXX  G\nand so does\nthe end\n";
+        let grid: Grid = map.parse().unwrap();
+        assert_eq!(grid.to_map_string(), map);
+        assert_eq!(
+            grid.to_map_string()
+                .parse::<Grid>()
+                .unwrap()
+                .to_map_string(),
+            map
+        );
+    }
+
+    #[test]
+    fn only_the_maps_it_writes_are_written_back_byte_for_byte() {
+        let map = "2\r\n3\r\nC.1\r\n.XG\r\n";
+        let written = map.parse::<Grid>().unwrap().to_map_string();
+        assert_ne!(written, map);
+        assert_eq!(written, "2\n3\nC  \n XG\n");
+        assert_eq!(written.parse::<Grid>().unwrap().to_map_string(), written);
+    }
+}
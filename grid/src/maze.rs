@@ -0,0 +1,368 @@
+use super::content::Content;
+use super::{get_seeded_rng, Grid};
+use rand::seq::SliceRandom;
+use rand::{thread_rng, Rng};
+use std::cmp::Ordering;
+
+//the cells of a maze are the ones with even coordinates
+//the ones between two of them are the passages, which can be open or a wall
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MazeAlgorithm {
+    //a random depth first search, with long corridors and few branches
+    RecursiveBacktracker,
+    //grows the maze from a random cell opening random passages of its border, with many short dead ends
+    Prim,
+    //joins random cells that aren't connected yet, with many short dead ends too
+    Kruskal,
+    //splits the grid with walls with a single gap, with long straight walls
+    RecursiveDivision,
+}
+
+impl Grid {
+    pub fn fill_maze(&mut self, algorithm: MazeAlgorithm, braid: f32) {
+        self.fill_maze_with_rng(algorithm, braid, &mut thread_rng());
+    }
+
+    pub fn fill_maze_with_seed(&mut self, algorithm: MazeAlgorithm, braid: f32, seed: u64) {
+        self.fill_maze_with_rng(algorithm, braid, &mut get_seeded_rng(seed));
+    }
+
+    //braid is the portion of the dead ends that are removed, joining them with another cell
+    //with 0 there is a single path between any two cells and with 1 there aren't dead ends
+    //the car and the goal are placed in two random cells, which are always connected
+    pub fn fill_maze_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        algorithm: MazeAlgorithm,
+        braid: f32,
+        rng: &mut R,
+    ) {
+        assert!((0.0..=1.0).contains(&braid));
+        self.car = None;
+        self.goal = None;
+        self.clear_path();
+        for cell in self.grid.iter_mut().flatten() {
+            *cell = Content::Wall;
+        }
+        let size = (self.n().div_ceil(2), self.m().div_ceil(2));
+        for y in 0..size.1 {
+            for x in 0..size.0 {
+                self.grid[2 * y][2 * x] = Content::Empty;
+            }
+        }
+        match algorithm {
+            MazeAlgorithm::RecursiveBacktracker => self.carve_backtracker(size, rng),
+            MazeAlgorithm::Prim => self.carve_prim(size, rng),
+            MazeAlgorithm::Kruskal => self.carve_kruskal(size, rng),
+            MazeAlgorithm::RecursiveDivision => self.divide(size, rng),
+        }
+        self.braid(size, braid, rng);
+
+        let car_pos = (rng.gen_range(0, size.0), rng.gen_range(0, size.1));
+        self.set_car(2 * car_pos.0, 2 * car_pos.1);
+        //if the maze only has one cell we won't place the goal
+        if size.0 * size.1 != 1 {
+            let goal_pos = loop {
+                let pos = (rng.gen_range(0, size.0), rng.gen_range(0, size.1));
+                if pos != car_pos {
+                    break pos;
+                }
+            };
+            self.set_goal(2 * goal_pos.0, 2 * goal_pos.1);
+        }
+    }
+
+    fn carve_backtracker<R: Rng + ?Sized>(&mut self, size: (usize, usize), rng: &mut R) {
+        let mut visited = vec![vec![false; size.0]; size.1];
+        let start = (rng.gen_range(0, size.0), rng.gen_range(0, size.1));
+        visited[start.1][start.0] = true;
+        let mut stack = vec![start];
+        while let Some(&current) = stack.last() {
+            let unvisited: Vec<(usize, usize)> = get_maze_neighbours(current, size)
+                .into_iter()
+                .filter(|neigh| !visited[neigh.1][neigh.0])
+                .collect();
+            match unvisited.choose(rng) {
+                Some(&next) => {
+                    self.open_passage(current, next);
+                    visited[next.1][next.0] = true;
+                    stack.push(next);
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    fn carve_prim<R: Rng + ?Sized>(&mut self, size: (usize, usize), rng: &mut R) {
+        let mut visited = vec![vec![false; size.0]; size.1];
+        let start = (rng.gen_range(0, size.0), rng.gen_range(0, size.1));
+        visited[start.1][start.0] = true;
+        //the passages between a visited cell and another one
+        let mut frontier: Vec<((usize, usize), (usize, usize))> = get_maze_neighbours(start, size)
+            .into_iter()
+            .map(|neigh| (start, neigh))
+            .collect();
+        while !frontier.is_empty() {
+            let (from, to) = frontier.swap_remove(rng.gen_range(0, frontier.len()));
+            if visited[to.1][to.0] {
+                continue;
+            }
+            self.open_passage(from, to);
+            visited[to.1][to.0] = true;
+            frontier.extend(
+                get_maze_neighbours(to, size)
+                    .into_iter()
+                    .filter(|neigh| !visited[neigh.1][neigh.0])
+                    .map(|neigh| (to, neigh)),
+            );
+        }
+    }
+
+    fn carve_kruskal<R: Rng + ?Sized>(&mut self, size: (usize, usize), rng: &mut R) {
+        let index = |pos: (usize, usize)| pos.1 * size.0 + pos.0;
+        let mut passages = Vec::new();
+        for y in 0..size.1 {
+            for x in 0..size.0 {
+                if x + 1 < size.0 {
+                    passages.push(((x, y), (x + 1, y)));
+                }
+                if y + 1 < size.1 {
+                    passages.push(((x, y), (x, y + 1)));
+                }
+            }
+        }
+        passages.shuffle(rng);
+        //every cell points to another one of its set, the root of the set points to itself
+        let mut parents: Vec<usize> = (0..size.0 * size.1).collect();
+        for (from, to) in passages {
+            let from_root = find_root(&mut parents, index(from));
+            let to_root = find_root(&mut parents, index(to));
+            if from_root != to_root {
+                parents[from_root] = to_root;
+                self.open_passage(from, to);
+            }
+        }
+    }
+
+    fn divide<R: Rng + ?Sized>(&mut self, size: (usize, usize), rng: &mut R) {
+        for y in 0..size.1 {
+            for x in 0..size.0 {
+                for neigh in get_maze_neighbours((x, y), size) {
+                    self.open_passage((x, y), neigh);
+                }
+            }
+        }
+        //the chambers are given by their first and last cells
+        let mut chambers = vec![((0, 0), (size.0 - 1, size.1 - 1))];
+        while let Some(((x0, y0), (x1, y1))) = chambers.pop() {
+            let (width, height) = (x1 - x0 + 1, y1 - y0 + 1);
+            if width < 2 || height < 2 {
+                continue;
+            }
+            //the longest side is split, so the chambers don't get too narrow
+            let horizontal = match width.cmp(&height) {
+                Ordering::Less => true,
+                Ordering::Greater => false,
+                Ordering::Equal => rng.gen(),
+            };
+            if horizontal {
+                //the wall goes between the rows y and y + 1
+                let y = rng.gen_range(y0, y1);
+                let gap = rng.gen_range(x0, x1 + 1);
+                for x in 2 * x0..=2 * x1 {
+                    if x != 2 * gap {
+                        self.grid[2 * y + 1][x] = Content::Wall;
+                    }
+                }
+                chambers.push(((x0, y0), (x1, y)));
+                chambers.push(((x0, y + 1), (x1, y1)));
+            } else {
+                let x = rng.gen_range(x0, x1);
+                let gap = rng.gen_range(y0, y1 + 1);
+                for y in 2 * y0..=2 * y1 {
+                    if y != 2 * gap {
+                        self.grid[y][2 * x + 1] = Content::Wall;
+                    }
+                }
+                chambers.push(((x0, y0), (x, y1)));
+                chambers.push(((x + 1, y0), (x1, y1)));
+            }
+        }
+    }
+
+    fn braid<R: Rng + ?Sized>(&mut self, size: (usize, usize), braid: f32, rng: &mut R) {
+        let mut cells: Vec<(usize, usize)> = (0..size.1)
+            .flat_map(|y| (0..size.0).map(move |x| (x, y)))
+            .collect();
+        cells.shuffle(rng);
+        for cell in cells {
+            let neighs = get_maze_neighbours(cell, size);
+            let n_open = neighs
+                .iter()
+                .filter(|&&neigh| self.is_passage_open(cell, neigh))
+                .count();
+            let closed: Vec<(usize, usize)> = neighs
+                .into_iter()
+                .filter(|&neigh| !self.is_passage_open(cell, neigh))
+                .collect();
+            //the dead ends removed before may have already joined this one
+            if n_open != 1 || closed.is_empty() || rng.gen::<f32>() >= braid {
+                continue;
+            }
+            let next = *closed.choose(rng).unwrap();
+            self.open_passage(cell, next);
+        }
+    }
+
+    //the positions are the ones of the cells of the maze, not the ones of the grid
+    fn open_passage(&mut self, from: (usize, usize), to: (usize, usize)) {
+        self.grid[from.1 + to.1][from.0 + to.0] = Content::Empty;
+    }
+
+    fn is_passage_open(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        !matches!(self.grid[from.1 + to.1][from.0 + to.0], Content::Wall)
+    }
+}
+
+fn get_maze_neighbours(pos: (usize, usize), size: (usize, usize)) -> Vec<(usize, usize)> {
+    let mut neighs = Vec::new();
+    if pos.0 > 0 {
+        neighs.push((pos.0 - 1, pos.1));
+    }
+    if pos.0 + 1 < size.0 {
+        neighs.push((pos.0 + 1, pos.1));
+    }
+    if pos.1 > 0 {
+        neighs.push((pos.0, pos.1 - 1));
+    }
+    if pos.1 + 1 < size.1 {
+        neighs.push((pos.0, pos.1 + 1));
+    }
+    neighs
+}
+
+fn find_root(parents: &mut [usize], mut index: usize) -> usize {
+    while parents[index] != index {
+        //halves the path to the root for the next searches
+        parents[index] = parents[parents[index]];
+        index = parents[index];
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MazeAlgorithm;
+    use crate::Grid;
+
+    const ALGORITHMS: [MazeAlgorithm; 4] = [
+        MazeAlgorithm::RecursiveBacktracker,
+        MazeAlgorithm::Prim,
+        MazeAlgorithm::Kruskal,
+        MazeAlgorithm::RecursiveDivision,
+    ];
+
+    fn get_open_cells(grid: &Grid) -> Vec<(usize, usize)> {
+        (0..grid.m())
+            .flat_map(|y| (0..grid.n()).map(move |x| (x, y)))
+            .filter(|&pos| grid.is_traversable(pos))
+            .collect()
+    }
+
+    //the pairs of open cells next to each other, each pair is counted once
+    fn count_open_pairs(grid: &Grid) -> usize {
+        get_open_cells(grid)
+            .into_iter()
+            .map(|(x, y)| {
+                let right = x + 1 < grid.n() && grid.is_traversable((x + 1, y));
+                let below = y + 1 < grid.m() && grid.is_traversable((x, y + 1));
+                right as usize + below as usize
+            })
+            .sum()
+    }
+
+    //a connected maze without loops is a tree, so it has one pair of open cells less than cells
+    #[test]
+    fn every_algorithm_generates_a_perfect_maze() {
+        for &algorithm in &ALGORITHMS {
+            for seed in 0..20 {
+                let mut grid = Grid::new(15, 21);
+                grid.fill_maze_with_seed(algorithm, 0.0, seed);
+                let (car, goal) = (grid.get_car().unwrap(), grid.get_goal().unwrap());
+                let n_open = get_open_cells(&grid).len();
+                assert_eq!(
+                    grid.get_reachable_cells(car).len(),
+                    n_open,
+                    "{:?} with seed {}",
+                    algorithm,
+                    seed
+                );
+                assert!(grid.is_connected(car, goal));
+                assert_eq!(
+                    count_open_pairs(&grid),
+                    n_open - 1,
+                    "{:?} with seed {}",
+                    algorithm,
+                    seed
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn a_seed_always_generates_the_same_maze() {
+        for &algorithm in &ALGORITHMS {
+            let mut grid = Grid::new(15, 21);
+            grid.fill_maze_with_seed(algorithm, 0.3, 7);
+            let mut other = Grid::new(15, 21);
+            other.fill_maze_with_seed(algorithm, 0.3, 7);
+            assert_eq!(
+                grid.to_map_string(),
+                other.to_map_string(),
+                "{:?}",
+                algorithm
+            );
+            other.fill_maze_with_seed(algorithm, 0.3, 8);
+            assert_ne!(
+                grid.to_map_string(),
+                other.to_map_string(),
+                "{:?}",
+                algorithm
+            );
+        }
+    }
+
+    //the cells of the maze are the ones with even coordinates, the passages always join two
+    #[test]
+    fn a_fully_braided_maze_has_no_dead_ends() {
+        for &algorithm in &ALGORITHMS {
+            for seed in 0..20 {
+                let mut grid = Grid::new(15, 21);
+                grid.fill_maze_with_seed(algorithm, 1.0, seed);
+                for (x, y) in get_open_cells(&grid) {
+                    if x % 2 != 0 || y % 2 != 0 {
+                        continue;
+                    }
+                    let n_open = [(-1, 0), (1, 0), (0, -1), (0, 1)]
+                        .iter()
+                        .map(|&(dx, dy)| (x as isize + dx, y as isize + dy))
+                        .filter(|&(x, y)| {
+                            x >= 0
+                                && y >= 0
+                                && grid.is_inside((x as usize, y as usize))
+                                && grid.is_traversable((x as usize, y as usize))
+                        })
+                        .count();
+                    assert!(
+                        n_open >= 2,
+                        "{:?} with seed {} at {:?}",
+                        algorithm,
+                        seed,
+                        (x, y)
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use super::content::Content;
+use super::map::MapError;
+use super::{Grid, Movement};
+
+//the format of the maps of the MovingAI benchmarks:
+//type octile
+//height <rows>
+//width <columns>
+//map
+//and a line for every row where . G and S are passable cells and @ O T and W are not
+//the moves in those maps are the ones of Movement::EightConnectedNoCornerCutting
+impl Grid {
+    pub fn from_moving_ai(map: &str) -> Result<Grid, MapError> {
+        let mut lines = map.lines();
+        parse_header(lines.next(), 1, "type", Some("octile"))?;
+        let m = parse_size(parse_header(lines.next(), 2, "height", None)?, 2, "height")?;
+        let n = parse_size(parse_header(lines.next(), 3, "width", None)?, 3, "width")?;
+        match lines.next() {
+            Some(line) if line.trim() == "map" => {}
+            line => return Err(unexpected_line(4, "\"map\"", line)),
+        }
+        let mut grid = Grid::new(m, n);
+        grid.set_movement(Movement::EightConnectedNoCornerCutting);
+        for i in 0..m {
+            let line_number = i + 5;
+            let row = lines.next().ok_or_else(|| {
+                MapError::new(
+                    line_number,
+                    1,
+                    &format!("row {} of {}", i + 1, m),
+                    "end of file",
+                )
+            })?;
+            let mut row_chars = row.chars();
+            for j in 0..n {
+                match row_chars.next() {
+                    Some('.') | Some('G') | Some('S') => {}
+                    Some('@') | Some('O') | Some('T') | Some('W') => grid.set_wall(j, i),
+                    Some(c) => {
+                        return Err(MapError::new(
+                            line_number,
+                            j + 1,
+                            "one of . G S @ O T W",
+                            &format!("'{}'", c),
+                        ))
+                    }
+                    None => {
+                        return Err(MapError::new(
+                            line_number,
+                            j + 1,
+                            &format!("{} cells in the row", n),
+                            &j.to_string(),
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(grid)
+    }
+
+    //the car, the goal and the weighted cells are written as passable cells
+    //as the format has no way to represent them
+    pub fn to_moving_ai_string(&self) -> String {
+        let mut map = format!(
+            "type octile\nheight {}\nwidth {}\nmap\n",
+            self.m(),
+            self.n()
+        );
+        for row in &self.grid {
+            for cell in row {
+                map.push(match cell {
+                    Content::Wall => '@',
+                    _ => '.',
+                });
+            }
+            map.push('\n');
+        }
+        map
+    }
+}
+
+//checks that the line is "<key> <value>" and returns the value
+fn parse_header<'a>(
+    line: Option<&'a str>,
+    line_number: usize,
+    key: &str,
+    value: Option<&str>,
+) -> Result<&'a str, MapError> {
+    let expected = match value {
+        Some(value) => format!("\"{} {}\"", key, value),
+        None => format!("\"{} <number>\"", key),
+    };
+    let line = line.ok_or_else(|| MapError::new(line_number, 1, &expected, "end of file"))?;
+    let mut words = line.split_whitespace();
+    match (words.next(), words.next(), words.next()) {
+        (Some(found_key), Some(found_value), None)
+            if found_key == key && (value.is_none() || value == Some(found_value)) =>
+        {
+            Ok(found_value)
+        }
+        _ => Err(unexpected_line(line_number, &expected, Some(line))),
+    }
+}
+
+fn parse_size(value: &str, line_number: usize, name: &str) -> Result<usize, MapError> {
+    match value.parse() {
+        Ok(0) | Err(_) => Err(MapError::new(
+            line_number,
+            name.len() + 2,
+            &format!("a positive {}", name),
+            &format!("\"{}\"", value),
+        )),
+        Ok(size) => Ok(size),
+    }
+}
+
+fn unexpected_line(line_number: usize, expected: &str, line: Option<&str>) -> MapError {
+    match line {
+        Some(line) => MapError::new(line_number, 1, expected, &format!("\"{}\"", line)),
+        None => MapError::new(line_number, 1, expected, "end of file"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::map::MapError;
+    use crate::Grid;
+
+    fn parse_error(map: &str) -> MapError {
+        match Grid::from_moving_ai(map) {
+            Ok(_) => panic!("The map should be invalid"),
+            Err(err) => err,
+        }
+    }
+
+    #[test]
+    fn a_bad_character_is_reported_in_its_column() {
+        assert_eq!(
+            parse_error("type octile\nheight 2\nwidth 3\nmap\n...\n.x.\n"),
+            MapError::new(6, 2, "one of . G S @ O T W", "'x'")
+        );
+    }
+
+    #[test]
+    fn a_missing_header_is_reported_in_its_line() {
+        assert_eq!(
+            parse_error("type octile\nwidth 3\n"),
+            MapError::new(2, 1, "\"height <number>\"", "\"width 3\"")
+        );
+        assert_eq!(
+            parse_error("type octile\nheight 2\nwidth 3\n"),
+            MapError::new(4, 1, "\"map\"", "end of file")
+        );
+    }
+
+    #[test]
+    fn a_map_is_written_as_it_was_read() {
+        let map = "type octile\nheight 3\nwidth 4\nmap\n..@.\n@...\n...@\n";
+        let grid = Grid::from_moving_ai(map).unwrap();
+        assert_eq!(grid.to_moving_ai_string(), map);
+    }
+}
@@ -0,0 +1,210 @@
+use super::{AStarNode, PathResult, SearchResult};
+use crate::content::Content;
+use crate::{Grid, Heuristic};
+use fxhash::FxHashMap;
+use std::collections::BinaryHeap;
+
+//how the successors of a node are generated
+#[derive(Clone, Copy, PartialEq)]
+enum Expansion {
+    Neighbours,
+    JumpPoints,
+}
+
+//an A* search that is advanced one expansion at a time with step
+//so the state of the search can be looked at or drawn between expansions
+pub struct AStarSearch<'a, H: Heuristic + ?Sized> {
+    grid: &'a Grid,
+    heuristic: &'a H,
+    expansion: Expansion,
+    start: (usize, usize),
+    goal: (usize, usize),
+    node_map: FxHashMap<(usize, usize), AStarNode>,
+    priority_queue: BinaryHeap<AStarNode>,
+    explored: Vec<(usize, usize)>,
+    iteration_count: usize,
+    current: Option<(usize, usize)>,
+    finished: bool,
+    found: bool,
+}
+
+impl<'a, H: Heuristic + ?Sized> AStarSearch<'a, H> {
+    fn new(
+        grid: &'a Grid,
+        start: (usize, usize),
+        goal: (usize, usize),
+        heuristic: &'a H,
+        expansion: Expansion,
+    ) -> Self {
+        assert!(grid.is_inside(start) && grid.is_inside(goal));
+        let mut node_map = FxHashMap::default();
+        //reserve space for twice the space needed for the expected length upfront to avoid reallocations
+        node_map.reserve(heuristic.estimate(start, goal) as usize * 2);
+        node_map.insert(
+            start,
+            AStarNode {
+                pos: start,
+                predecessor: None,
+                dist: 0.0,
+                guessed_dist: heuristic.estimate(start, goal),
+                depth: 0,
+            },
+        );
+        let mut priority_queue = BinaryHeap::new();
+        priority_queue.push(node_map[&start]);
+        AStarSearch {
+            grid,
+            heuristic,
+            expansion,
+            start,
+            goal,
+            node_map,
+            priority_queue,
+            explored: Vec::new(),
+            iteration_count: 0,
+            current: None,
+            finished: false,
+            found: false,
+        }
+    }
+
+    //expands the next node, returns false if the search had already finished
+    pub fn step(&mut self) -> bool {
+        if self.finished {
+            return false;
+        }
+        let current = loop {
+            match self.priority_queue.pop() {
+                //the node was already expanded with a shorter distance
+                Some(node) if node.dist > self.node_map[&node.pos].dist => continue,
+                Some(node) => break node,
+                None => {
+                    self.current = None;
+                    self.finished = true;
+                    return true;
+                }
+            }
+        };
+        self.iteration_count += 1;
+        self.current = Some(current.pos);
+        if current.pos == self.goal {
+            self.finished = true;
+            self.found = true;
+            return true;
+        }
+        if current.pos != self.start {
+            self.explored.push(current.pos);
+        }
+        for (neigh_pos, cost) in self.get_successors(&current) {
+            let dist = current.dist + cost;
+            if dist < self.node_map.get(&neigh_pos).unwrap_or_default().dist {
+                let neigh_node = self.node_map.entry(neigh_pos).or_default();
+                neigh_node.pos = neigh_pos;
+                neigh_node.predecessor = Some(current.pos);
+                neigh_node.dist = dist;
+                neigh_node.guessed_dist = dist + self.heuristic.estimate(neigh_pos, self.goal);
+                neigh_node.depth = current.depth + 1;
+                self.priority_queue.push(*neigh_node);
+            }
+        }
+        true
+    }
+
+    //the successors of a node are given with the cost to reach them from it
+    fn get_successors(&self, node: &AStarNode) -> Vec<((usize, usize), f32)> {
+        match self.expansion {
+            Expansion::Neighbours => self
+                .grid
+                .get_neighbours(node.pos)
+                .into_iter()
+                .map(|neigh_pos| (neigh_pos, self.grid.get_move_cost(node.pos, neigh_pos)))
+                .collect(),
+            Expansion::JumpPoints => self.grid.get_jump_successors(node, self.goal),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    //the cell expanded by the last step
+    pub fn get_current(&self) -> Option<(usize, usize)> {
+        self.current
+    }
+
+    //the cells waiting to be expanded, without the outdated entries of the queue
+    pub fn get_open_set(&self) -> Vec<(usize, usize)> {
+        self.priority_queue
+            .iter()
+            .filter(|node| node.dist <= self.node_map[&node.pos].dist)
+            .map(|node| node.pos)
+            .collect()
+    }
+
+    //the expanded cells in the order they were expanded, without the start and the goal
+    pub fn get_closed_set(&self) -> &[(usize, usize)] {
+        &self.explored
+    }
+
+    //runs the search until it finishes
+    pub fn into_result(mut self) -> SearchResult {
+        while self.step() {}
+        let path = if self.found {
+            Some(PathResult::new(
+                &self.node_map,
+                self.start,
+                self.goal,
+                self.iteration_count,
+            ))
+        } else {
+            None
+        };
+        SearchResult {
+            explored: self.explored,
+            explored_backward: Vec::new(),
+            n_explored: self.iteration_count,
+            path,
+        }
+    }
+}
+
+impl Grid {
+    //the same search as search, but it has to be advanced by hand
+    pub fn search_steps<'a, H: Heuristic + ?Sized>(
+        &'a self,
+        start: (usize, usize),
+        goal: (usize, usize),
+        heuristic: &'a H,
+    ) -> AStarSearch<'a, H> {
+        AStarSearch::new(self, start, goal, heuristic, Expansion::Neighbours)
+    }
+
+    //the same search as jump_point_search, but it has to be advanced by hand
+    pub fn jump_point_search_steps<'a, H: Heuristic + ?Sized>(
+        &'a self,
+        start: (usize, usize),
+        goal: (usize, usize),
+        heuristic: &'a H,
+    ) -> AStarSearch<'a, H> {
+        let expansion = if self.is_uniform() {
+            Expansion::JumpPoints
+        } else {
+            Expansion::Neighbours
+        };
+        AStarSearch::new(self, start, goal, heuristic, expansion)
+    }
+
+    //draws the state of a search that is still running
+    pub fn draw_search_steps<H: Heuristic + ?Sized>(&mut self, search: &AStarSearch<H>) {
+        self.clear_path();
+        for &pos in search.get_closed_set() {
+            self.draw_mark(pos, Content::Explored);
+        }
+        for pos in search.get_open_set() {
+            self.draw_mark(pos, Content::Open);
+        }
+        if let Some(pos) = search.get_current() {
+            self.draw_mark(pos, Content::Current);
+        }
+    }
+}
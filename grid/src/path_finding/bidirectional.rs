@@ -0,0 +1,239 @@
+use super::{AStarNode, PathResult, SearchResult};
+use crate::{Grid, Heuristic};
+use fxhash::FxHashMap;
+use std::collections::BinaryHeap;
+
+//one of the two searches, the backward one goes from the goal to the start
+//so the predecessors of its nodes are their next cell in the path
+struct Frontier {
+    node_map: FxHashMap<(usize, usize), AStarNode>,
+    priority_queue: BinaryHeap<AStarNode>,
+    explored: Vec<(usize, usize)>,
+    origin: (usize, usize),
+    target: (usize, usize),
+}
+
+impl Frontier {
+    fn new<H: Heuristic + ?Sized>(
+        origin: (usize, usize),
+        target: (usize, usize),
+        heuristic: &H,
+    ) -> Self {
+        let node = AStarNode {
+            pos: origin,
+            predecessor: None,
+            dist: 0.0,
+            guessed_dist: get_potential(origin, origin, target, heuristic),
+            depth: 0,
+        };
+        let mut node_map = FxHashMap::default();
+        node_map.insert(origin, node);
+        let mut priority_queue = BinaryHeap::new();
+        priority_queue.push(node);
+        Frontier {
+            node_map,
+            priority_queue,
+            explored: Vec::new(),
+            origin,
+            target,
+        }
+    }
+
+    //the smallest key that can still be expanded
+    fn get_min_guess(&mut self) -> f32 {
+        self.skip_outdated();
+        self.priority_queue
+            .peek()
+            .map_or(f32::INFINITY, |node| node.guessed_dist)
+    }
+
+    fn skip_outdated(&mut self) {
+        while let Some(node) = self.priority_queue.peek() {
+            if node.dist > self.node_map[&node.pos].dist {
+                self.priority_queue.pop();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn get_dist(&self, pos: (usize, usize)) -> f32 {
+        self.node_map
+            .get(&pos)
+            .map_or(f32::INFINITY, |node| node.dist)
+    }
+
+    //the cells from pos to the origin of the search
+    fn get_chain(&self, pos: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut chain = vec![pos];
+        let mut current = pos;
+        while let Some(predecessor) = self.node_map[&current].predecessor {
+            chain.push(predecessor);
+            current = predecessor;
+        }
+        chain
+    }
+}
+
+//both searches use the average of the heuristics to their target and to their origin
+//that way the keys of both are consistent with each other, so the search can stop as soon as
+//the smallest keys of both sides add up to the cost of the best path found
+fn get_potential<H: Heuristic + ?Sized>(
+    pos: (usize, usize),
+    origin: (usize, usize),
+    target: (usize, usize),
+    heuristic: &H,
+) -> f32 {
+    (heuristic.estimate(pos, target) - heuristic.estimate(pos, origin)) / 2.0
+}
+
+impl Grid {
+    //searches from the start and from the goal at the same time until both searches meet
+    //the path has the same cost as the one of search
+    pub fn bidirectional_search<H: Heuristic + ?Sized>(
+        &self,
+        start: (usize, usize),
+        goal: (usize, usize),
+        heuristic: &H,
+    ) -> SearchResult {
+        assert!(self.is_inside(start) && self.is_inside(goal));
+        let mut forward = Frontier::new(start, goal, heuristic);
+        let mut backward = Frontier::new(goal, start, heuristic);
+        //the cost of the best path found and the cell where both searches met in it
+        let mut best: Option<(f32, (usize, usize))> = if start == goal {
+            Some((0.0, start))
+        } else {
+            None
+        };
+        let mut iteration_count = 0;
+
+        loop {
+            let best_cost = best.map_or(f32::INFINITY, |(cost, _)| cost);
+            //no path through the nodes left in either search can be cheaper than the best one
+            if forward.get_min_guess() + backward.get_min_guess() >= best_cost {
+                break;
+            }
+            //the search with less open nodes is expanded
+            let is_forward = forward.priority_queue.len() <= backward.priority_queue.len();
+            let (current_frontier, other_frontier) = if is_forward {
+                (&mut forward, &backward)
+            } else {
+                (&mut backward, &forward)
+            };
+            let current = current_frontier.priority_queue.pop().unwrap();
+            iteration_count += 1;
+            if current.pos != start && current.pos != goal {
+                current_frontier.explored.push(current.pos);
+            }
+            for neigh_pos in self.get_neighbours(current.pos) {
+                //going backwards, the move is from the neighbour to the current cell
+                let cost = if is_forward {
+                    self.get_move_cost(current.pos, neigh_pos)
+                } else {
+                    self.get_move_cost(neigh_pos, current.pos)
+                };
+                let dist = current.dist + cost;
+                if dist < current_frontier.get_dist(neigh_pos) {
+                    let (origin, target) = (current_frontier.origin, current_frontier.target);
+                    let neigh_node = current_frontier.node_map.entry(neigh_pos).or_default();
+                    neigh_node.pos = neigh_pos;
+                    neigh_node.predecessor = Some(current.pos);
+                    neigh_node.dist = dist;
+                    neigh_node.guessed_dist =
+                        dist + get_potential(neigh_pos, origin, target, heuristic);
+                    neigh_node.depth = current.depth + 1;
+                    current_frontier.priority_queue.push(*neigh_node);
+                    let path_cost = dist + other_frontier.get_dist(neigh_pos);
+                    if path_cost < best.map_or(f32::INFINITY, |(cost, _)| cost) {
+                        best = Some((path_cost, neigh_pos));
+                    }
+                }
+            }
+        }
+
+        let path = best.map(|(cost, meeting_point)| {
+            let mut path = forward.get_chain(meeting_point);
+            path.reverse();
+            path.extend(backward.get_chain(meeting_point).into_iter().skip(1));
+            PathResult::from_path(path, cost, iteration_count)
+        });
+        SearchResult {
+            explored: forward.explored,
+            explored_backward: backward.explored,
+            n_explored: iteration_count,
+            path,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{assert_same_cost, get_best_cost, get_random_grids};
+    use crate::heuristic::Octile;
+    use crate::{Grid, Movement};
+
+    const MOVEMENTS: [Movement; 3] = [
+        Movement::FourConnected,
+        Movement::EightConnected,
+        Movement::EightConnectedNoCornerCutting,
+    ];
+
+    #[test]
+    fn finds_a_path_without_moves_if_the_start_is_the_goal() {
+        for &movement in &MOVEMENTS {
+            let mut grid = Grid::new(3, 4);
+            grid.set_movement(movement);
+            grid.set_wall(1, 1);
+            let result = grid.bidirectional_search((2, 1), (2, 1), &Octile);
+            let path = result.get_path().unwrap();
+            assert_eq!(path.get_path(), &[(2, 1)]);
+            assert!(path.get_directions().is_empty());
+            assert_eq!(path.get_cost(), 0.0);
+            assert!(result.get_explored().is_empty());
+            assert!(result.get_explored_backward().is_empty());
+        }
+    }
+
+    #[test]
+    fn finds_no_path_between_separated_halves() {
+        for &movement in &MOVEMENTS {
+            let mut grid = Grid::new(4, 7);
+            grid.set_movement(movement);
+            for y in 0..4 {
+                grid.set_wall(3, y);
+            }
+            let result = grid.bidirectional_search((0, 1), (6, 2), &Octile);
+            assert!(result.get_path().is_none(), "{:?}", movement);
+            assert!(grid.search((0, 1), (6, 2), &Octile).get_path().is_none());
+        }
+    }
+
+    //the search ends as soon as one of the sides has nothing left to expand
+    #[test]
+    fn stops_when_the_goal_is_closed_in() {
+        for &movement in &MOVEMENTS {
+            let mut grid = Grid::new(5, 6);
+            grid.set_movement(movement);
+            for &(x, y) in &[(4, 3), (5, 3), (4, 4)] {
+                grid.set_wall(x, y);
+            }
+            let result = grid.bidirectional_search((0, 0), (5, 4), &Octile);
+            assert!(result.get_path().is_none(), "{:?}", movement);
+            assert!(result.get_explored_backward().is_empty());
+            assert!(result.get_explored().len() < 5 * 6 - 4);
+        }
+    }
+
+    #[test]
+    fn finds_paths_as_cheap_as_a_star() {
+        for grid in get_random_grids() {
+            let (car, goal) = (grid.get_car().unwrap(), grid.get_goal().unwrap());
+            let result = grid.bidirectional_search(car, goal, &Octile);
+            assert_same_cost(
+                &grid,
+                get_best_cost(&grid),
+                result.get_path().map(|path| path.get_cost()),
+            );
+        }
+    }
+}
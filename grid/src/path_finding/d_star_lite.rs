@@ -0,0 +1,445 @@
+use super::{Movement, PathResult, SearchResult};
+use crate::content::Direction;
+use crate::{Grid, Heuristic};
+use fxhash::FxHashMap;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+//the relative difference between a key and the start's that is still taken as equal
+//it can only make the search expand a few more nodes, never stop earlier
+const KEY_TOLERANCE: f32 = 1e-4;
+
+#[derive(Copy, Clone, PartialEq)]
+struct Key(f32, f32);
+
+impl Key {
+    //keys are compared lexicographically
+    fn cmp(&self, other: &Key) -> Ordering {
+        //this will panic if a key is NaN. That shouldn't happen as the costs are never negative
+        match self.0.partial_cmp(&other.0).unwrap() {
+            Ordering::Equal => self.1.partial_cmp(&other.1).unwrap(),
+            x => x,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+struct QueueEntry {
+    key: Key,
+    pos: (usize, usize),
+}
+
+impl Eq for QueueEntry {}
+
+impl PartialEq for QueueEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Ord for QueueEntry {
+    //inversed so the entry with the smallest key has more priority
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.key.cmp(&self.key)
+    }
+}
+
+impl PartialOrd for QueueEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+//D* Lite keeps its search between calls, so when cells change or the car moves
+//the path is repaired instead of searched again from scratch
+//it searches from the goal to the car, so the distances to the goal stay valid when the car moves
+pub struct DStarLite<H: Heuristic> {
+    heuristic: H,
+    start: (usize, usize),
+    goal: (usize, usize),
+    last_start: (usize, usize),
+    key_modifier: f32,
+    dist: FxHashMap<(usize, usize), f32>,
+    lookahead: FxHashMap<(usize, usize), f32>,
+    queue: BinaryHeap<QueueEntry>,
+    //the current key of every node in the queue, the entries with another key are outdated
+    queued: FxHashMap<(usize, usize), Key>,
+    size: (usize, usize),
+}
+
+impl<H: Heuristic> DStarLite<H> {
+    pub fn new(grid: &Grid, start: (usize, usize), goal: (usize, usize), heuristic: H) -> Self {
+        assert!(grid.is_inside(start) && grid.is_inside(goal));
+        let mut planner = DStarLite {
+            heuristic,
+            start,
+            goal,
+            last_start: start,
+            key_modifier: 0.0,
+            dist: FxHashMap::default(),
+            lookahead: FxHashMap::default(),
+            queue: BinaryHeap::new(),
+            queued: FxHashMap::default(),
+            size: (grid.m(), grid.n()),
+        };
+        planner.lookahead.insert(goal, 0.0);
+        planner.push(goal);
+        planner
+    }
+
+    pub fn get_start(&self) -> (usize, usize) {
+        self.start
+    }
+
+    pub fn get_goal(&self) -> (usize, usize) {
+        self.goal
+    }
+
+    //the car moved, the next call to plan will search from the new position
+    pub fn move_start(&mut self, start: (usize, usize)) {
+        self.key_modifier += self.heuristic.estimate(self.last_start, start);
+        self.last_start = start;
+        self.start = start;
+    }
+
+    //the cells changed in the grid, so the moves from and to them may have changed too
+    pub fn update_cells(&mut self, grid: &Grid, cells: &[(usize, usize)]) {
+        self.check_size(grid);
+        for &cell in cells {
+            //a cell can also allow or block the diagonal moves that go by its corners
+            //all of them start and end next to it
+            self.update_node(grid, cell);
+            for neigh in get_adjacent(grid, cell) {
+                self.update_node(grid, neigh);
+            }
+        }
+    }
+
+    //repairs the path with the changes since the last call
+    //the explored cells are the ones that had to be updated to do it
+    pub fn plan(&mut self, grid: &Grid) -> SearchResult {
+        self.check_size(grid);
+        let mut explored = Vec::new();
+        self.compute_shortest_path(grid, &mut explored);
+        let n_explored = explored.len();
+        explored.retain(|&pos| pos != self.start && pos != self.goal);
+        let path = self.extract_path(grid, n_explored);
+        SearchResult {
+            explored,
+            explored_backward: Vec::new(),
+            n_explored,
+            path,
+        }
+    }
+
+    fn compute_shortest_path(&mut self, grid: &Grid, explored: &mut Vec<(usize, usize)>) {
+        loop {
+            let start_key = self.calculate_key(self.start);
+            let top = match self.pop() {
+                Some(top) => top,
+                None => break,
+            };
+            //with diagonal moves the nodes of the path can end up with a key slightly bigger
+            //than the start's because of the rounding, so the ones almost equal are updated too
+            let tolerance = KEY_TOLERANCE * start_key.0.max(1.0);
+            if top.key.0 > start_key.0 + tolerance
+                && self.get_lookahead(self.start) == self.get_dist(self.start)
+            {
+                //it will be needed in the next call
+                self.push(top.pos);
+                break;
+            }
+            let pos = top.pos;
+            let new_key = self.calculate_key(pos);
+            if top.key.cmp(&new_key) == Ordering::Less {
+                self.push(pos);
+                continue;
+            }
+            explored.push(pos);
+            if self.get_dist(pos) > self.get_lookahead(pos) {
+                self.dist.insert(pos, self.get_lookahead(pos));
+            } else {
+                self.dist.insert(pos, f32::INFINITY);
+                self.update_node(grid, pos);
+            }
+            for neigh in get_adjacent(grid, pos) {
+                self.update_node(grid, neigh);
+            }
+        }
+    }
+
+    fn update_node(&mut self, grid: &Grid, pos: (usize, usize)) {
+        if pos != self.goal {
+            let lookahead = get_successors(grid, pos)
+                .into_iter()
+                .map(|(succ, cost)| cost + self.get_dist(succ))
+                .fold(f32::INFINITY, f32::min);
+            self.lookahead.insert(pos, lookahead);
+        }
+        self.queued.remove(&pos);
+        if self.get_dist(pos) != self.get_lookahead(pos) {
+            self.push(pos);
+        }
+    }
+
+    //follows the cheapest successors from the start, which lead to the goal once the search is done
+    fn extract_path(&self, grid: &Grid, n_explored: usize) -> Option<PathResult> {
+        if self.get_lookahead(self.start).is_infinite() {
+            return None;
+        }
+        let mut path = vec![self.start];
+        let mut cost = 0.0;
+        let mut current = self.start;
+        while current != self.goal {
+            let (next, move_cost) = get_successors(grid, current).into_iter().min_by(|a, b| {
+                (a.1 + self.get_dist(a.0))
+                    .partial_cmp(&(b.1 + self.get_dist(b.0)))
+                    .unwrap()
+            })?;
+            //a loop means that the search wasn't repaired properly
+            if path.len() > self.size.0 * self.size.1 || self.get_dist(next).is_infinite() {
+                return None;
+            }
+            cost += move_cost;
+            path.push(next);
+            current = next;
+        }
+        Some(PathResult::from_path(path, cost, n_explored))
+    }
+
+    fn calculate_key(&self, pos: (usize, usize)) -> Key {
+        let dist = self.get_dist(pos).min(self.get_lookahead(pos));
+        Key(
+            dist + self.heuristic.estimate(pos, self.start) + self.key_modifier,
+            dist,
+        )
+    }
+
+    fn push(&mut self, pos: (usize, usize)) {
+        let key = self.calculate_key(pos);
+        self.queued.insert(pos, key);
+        self.queue.push(QueueEntry { key, pos });
+    }
+
+    //skips the outdated entries
+    fn pop(&mut self) -> Option<QueueEntry> {
+        while let Some(entry) = self.queue.pop() {
+            if self.queued.get(&entry.pos) == Some(&entry.key) {
+                self.queued.remove(&entry.pos);
+                return Some(entry);
+            }
+        }
+        None
+    }
+
+    fn get_dist(&self, pos: (usize, usize)) -> f32 {
+        *self.dist.get(&pos).unwrap_or(&f32::INFINITY)
+    }
+
+    fn get_lookahead(&self, pos: (usize, usize)) -> f32 {
+        *self.lookahead.get(&pos).unwrap_or(&f32::INFINITY)
+    }
+
+    fn check_size(&self, grid: &Grid) {
+        assert!(
+            self.size == (grid.m(), grid.n()),
+            "The grid was resized, a new planner is needed"
+        );
+    }
+}
+
+//the cells that can be reached with a move from pos and the cost of each move
+fn get_successors(grid: &Grid, pos: (usize, usize)) -> Vec<((usize, usize), f32)> {
+    if !grid.is_traversable(pos) {
+        return Vec::new();
+    }
+    grid.get_neighbours(pos)
+        .into_iter()
+        .map(|neigh| (neigh, grid.get_move_cost(pos, neigh)))
+        .collect()
+}
+
+//every cell that could be connected to pos by a move, even walls
+fn get_adjacent(grid: &Grid, pos: (usize, usize)) -> Vec<(usize, usize)> {
+    let diagonals: &[Direction] = if grid.get_movement() == Movement::FourConnected {
+        &[]
+    } else {
+        &Direction::DIAGONAL
+    };
+    Direction::ORTHOGONAL
+        .iter()
+        .chain(diagonals.iter())
+        .filter_map(|dir| dir.apply(pos))
+        .filter(|&neigh| grid.is_inside(neigh))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{assert_same_cost, get_best_cost, get_random_grids};
+    use super::DStarLite;
+    use crate::heuristic::Octile;
+    use crate::{Grid, Movement};
+
+    const MOVEMENTS: [Movement; 3] = [
+        Movement::FourConnected,
+        Movement::EightConnected,
+        Movement::EightConnectedNoCornerCutting,
+    ];
+
+    fn get_cost(grid: &Grid, start: (usize, usize), goal: (usize, usize)) -> Option<f32> {
+        grid.search(start, goal, &Octile)
+            .into_path()
+            .map(|path| path.get_cost())
+    }
+
+    //the car takes a step and then the middle of the rest of its path becomes a wall,
+    //until the goal can't be reached anymore
+    #[test]
+    fn repairs_the_path_when_a_wall_is_added_on_it() {
+        for &movement in &MOVEMENTS {
+            let mut grid = Grid::new(5, 7);
+            grid.set_movement(movement);
+            let goal = (6, 2);
+            let mut planner = DStarLite::new(&grid, (0, 2), goal, Octile);
+            let mut path = planner.plan(&grid).into_path().unwrap().get_path().to_vec();
+            while path.len() > 2 {
+                let start = path[1];
+                let blocked = path[path.len() / 2 + 1];
+                grid.set_wall(blocked.0, blocked.1);
+                planner.move_start(start);
+                planner.update_cells(&grid, &[blocked]);
+                let repaired = match planner.plan(&grid).into_path() {
+                    Some(repaired) => repaired,
+                    None => {
+                        assert_eq!(get_cost(&grid, start, goal), None, "{:?}", movement);
+                        break;
+                    }
+                };
+                assert_eq!(repaired.get_path().first(), Some(&start));
+                assert!(!repaired.get_path().contains(&blocked));
+                let expected = get_cost(&grid, start, goal).unwrap();
+                assert!(
+                    (repaired.get_cost() - expected).abs() < 1e-4,
+                    "{:?}: expected {}, found {}",
+                    movement,
+                    expected,
+                    repaired.get_cost()
+                );
+                path = repaired.get_path().to_vec();
+            }
+        }
+    }
+
+    //the wall between the car and the goal has a gap at the bottom, until its top is removed
+    #[test]
+    fn repairs_the_path_when_a_wall_next_to_it_is_removed() {
+        for &movement in &MOVEMENTS {
+            let mut grid = Grid::new(5, 7);
+            grid.set_movement(movement);
+            for y in 0..4 {
+                grid.set_wall(3, y);
+            }
+            let mut planner = DStarLite::new(&grid, (0, 0), (6, 0), Octile);
+            let path = planner.plan(&grid).into_path().unwrap();
+            let expected = get_cost(&grid, (0, 0), (6, 0)).unwrap();
+            assert!((path.get_cost() - expected).abs() < 1e-4, "{:?}", movement);
+            grid.set_empty(3, 0);
+            planner.update_cells(&grid, &[(3, 0)]);
+            let path = planner.plan(&grid).into_path().unwrap();
+            assert_eq!(
+                path.get_path(),
+                &[(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0), (6, 0)]
+            );
+            assert_eq!(path.get_cost(), 6.0);
+        }
+    }
+
+    #[test]
+    fn finds_paths_as_cheap_as_a_star() {
+        for grid in get_random_grids() {
+            let (car, goal) = (grid.get_car().unwrap(), grid.get_goal().unwrap());
+            let result = DStarLite::new(&grid, car, goal, Octile).plan(&grid);
+            assert_same_cost(
+                &grid,
+                get_best_cost(&grid),
+                result.get_path().map(|path| path.get_cost()),
+            );
+        }
+    }
+
+    //the car moves along the path and a cell of the rest of the path becomes a wall,
+    //while a wall somewhere else is removed
+    #[test]
+    fn repairs_paths_as_cheap_as_a_star_while_moving() {
+        for mut grid in get_random_grids() {
+            let (car, goal) = (grid.get_car().unwrap(), grid.get_goal().unwrap());
+            let mut planner = DStarLite::new(&grid, car, goal, Octile);
+            let path = match planner.plan(&grid).into_path() {
+                Some(path) if path.get_path().len() > 4 => path.get_path().to_vec(),
+                _ => continue,
+            };
+            let start = path[2];
+            let blocked = path[path.len() / 2 + 1];
+            let mut changed = vec![blocked];
+            grid.set_wall(blocked.0, blocked.1);
+            if let Some(wall) = (0..grid.m())
+                .flat_map(|y| (0..grid.n()).map(move |x| (x, y)))
+                .find(|&pos| !grid.is_traversable(pos) && pos != blocked)
+            {
+                grid.set_empty(wall.0, wall.1);
+                changed.push(wall);
+            }
+            planner.move_start(start);
+            planner.update_cells(&grid, &changed);
+            let expected = grid
+                .search(start, goal, &Octile)
+                .get_path()
+                .map(|path| path.get_cost());
+            let result = planner.plan(&grid);
+            assert_same_cost(
+                &grid,
+                expected,
+                result.get_path().map(|path| path.get_cost()),
+            );
+            if let Some(path) = result.get_path() {
+                assert_eq!(path.get_path().first(), Some(&start));
+                assert!(!path.get_path().contains(&blocked));
+            }
+        }
+    }
+
+    //found in the random grids: after the repair the keys of the path add up the costs of the
+    //diagonal moves in another order than the start's, so they're a bit bigger than its key
+    //and the search would stop before the start is reached if they were compared exactly
+    #[test]
+    fn keys_almost_equal_to_the_start_are_expanded() {
+        let rows = [
+            "2X  X7    4    9",
+            " 4    9X  X6  XX",
+            "XXC    3X   X X ",
+            "X  X X  5 X  2 G",
+            "   X2  X 7X   4 ",
+            "X X X4   XX    X",
+            " 9XXX 6 XXXXXXXX",
+            "X X   XX    5  X",
+            "X  5  X 2  X 7  ",
+            "    7  XX4X   9 ",
+            "XX  X9X  XX   X3",
+            " 6  X 3    8    ",
+        ];
+        let map = format!("12\n16\n{}\n", rows.join("\n"));
+        let mut grid: Grid = map.parse().unwrap();
+        grid.set_movement(Movement::EightConnected);
+        let goal = (15, 3);
+        let mut planner = DStarLite::new(&grid, (2, 2), goal, Octile);
+        assert!(planner.plan(&grid).get_path().is_some());
+        grid.set_wall(10, 2);
+        grid.set_empty(1, 0);
+        planner.move_start((4, 2));
+        planner.update_cells(&grid, &[(10, 2), (1, 0)]);
+        let path = planner.plan(&grid).into_path().unwrap();
+        let expected = get_cost(&grid, (4, 2), goal).unwrap();
+        assert!((path.get_cost() - expected).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,306 @@
+use super::{AStarNode, Movement, SearchResult};
+use crate::content::{Content, Direction};
+use crate::{Grid, Heuristic};
+use std::cmp::{max, min};
+
+impl Grid {
+    //finds a path with the same cost as search, but only the jump points of the grid are expanded
+    //every cell must cost the same for that, so with weighted cells it falls back to search
+    pub fn jump_point_search<H: Heuristic + ?Sized>(
+        &self,
+        start: (usize, usize),
+        goal: (usize, usize),
+        heuristic: &H,
+    ) -> SearchResult {
+        self.jump_point_search_steps(start, goal, heuristic)
+            .into_result()
+    }
+
+    //the jump points that can be reached from a node with the cost to reach them
+    pub(super) fn get_jump_successors(
+        &self,
+        node: &AStarNode,
+        goal: (usize, usize),
+    ) -> Vec<((usize, usize), f32)> {
+        self.get_jump_directions(node.pos, node.predecessor)
+            .into_iter()
+            .filter_map(|dir| self.jump(node.pos, dir, goal))
+            .map(|jump_point| (jump_point, get_jump_cost(node.pos, jump_point)))
+            .collect()
+    }
+
+    pub(super) fn is_uniform(&self) -> bool {
+        let car_terrain = self.car.map(|car| self.get_terrain(car));
+        !matches!(car_terrain, Some(Content::Weighted(_)))
+            && self
+                .grid
+                .iter()
+                .flatten()
+                .all(|cell| !matches!(cell, Content::Weighted(_)))
+    }
+
+    //the directions worth jumping to after arriving to pos from its predecessor
+    //the rest can be reached with a path at least as short without going through pos
+    fn get_jump_directions(
+        &self,
+        pos: (usize, usize),
+        predecessor: Option<(usize, usize)>,
+    ) -> Vec<(isize, isize)> {
+        let (dx, dy) = match predecessor {
+            Some(predecessor) => get_jump_direction(predecessor, pos),
+            None => {
+                let mut dirs = vec![(1, 0), (-1, 0), (0, 1), (0, -1)];
+                if self.movement != Movement::FourConnected {
+                    dirs.extend(&[(1, 1), (1, -1), (-1, 1), (-1, -1)]);
+                }
+                return dirs;
+            }
+        };
+        let is_free = |x, y| self.is_traversable_at(pos, (x, y));
+        match self.movement {
+            Movement::EightConnected if dx != 0 && dy != 0 => {
+                let mut dirs = vec![(dx, 0), (0, dy), (dx, dy)];
+                if !is_free(-dx, 0) {
+                    dirs.push((-dx, dy));
+                }
+                if !is_free(0, -dy) {
+                    dirs.push((dx, -dy));
+                }
+                dirs
+            }
+            Movement::EightConnected if dx != 0 => {
+                let mut dirs = vec![(dx, 0)];
+                if !is_free(0, 1) {
+                    dirs.push((dx, 1));
+                }
+                if !is_free(0, -1) {
+                    dirs.push((dx, -1));
+                }
+                dirs
+            }
+            Movement::EightConnected => {
+                let mut dirs = vec![(0, dy)];
+                if !is_free(1, 0) {
+                    dirs.push((1, dy));
+                }
+                if !is_free(-1, 0) {
+                    dirs.push((-1, dy));
+                }
+                dirs
+            }
+            Movement::EightConnectedNoCornerCutting if dx != 0 && dy != 0 => {
+                vec![(dx, 0), (0, dy), (dx, dy)]
+            }
+            Movement::EightConnectedNoCornerCutting if dx != 0 => {
+                vec![(dx, 0), (dx, 1), (dx, -1), (0, 1), (0, -1)]
+            }
+            Movement::EightConnectedNoCornerCutting => {
+                vec![(0, dy), (1, dy), (-1, dy), (1, 0), (-1, 0)]
+            }
+            Movement::FourConnected if dx != 0 => vec![(dx, 0), (0, 1), (0, -1)],
+            Movement::FourConnected => vec![(0, dy), (1, 0), (-1, 0)],
+        }
+    }
+
+    //moves from pos in a straight line until finding a jump point
+    //returns None if a wall or the border of the grid is reached first
+    fn jump(
+        &self,
+        pos: (usize, usize),
+        dir: (isize, isize),
+        goal: (usize, usize),
+    ) -> Option<(usize, usize)> {
+        let mut current = pos;
+        loop {
+            current = self.step(current, dir)?;
+            if current == goal || self.has_forced_neighbour(current, dir) {
+                return Some(current);
+            }
+            //diagonal jumps stop where a straight jump would find a jump point
+            //without diagonal moves, vertical jumps do the same with the horizontal ones
+            let turns = if dir.0 != 0 && dir.1 != 0 {
+                [(dir.0, 0), (0, dir.1)]
+            } else if dir.1 != 0 && self.movement == Movement::FourConnected {
+                [(1, 0), (-1, 0)]
+            } else {
+                continue;
+            };
+            if turns
+                .iter()
+                .any(|&turn| self.jump(current, turn, goal).is_some())
+            {
+                return Some(current);
+            }
+        }
+    }
+
+    fn has_forced_neighbour(&self, pos: (usize, usize), (dx, dy): (isize, isize)) -> bool {
+        let is_free = |x, y| self.is_traversable_at(pos, (x, y));
+        match self.movement {
+            Movement::EightConnected if dx != 0 && dy != 0 => {
+                (is_free(-dx, dy) && !is_free(-dx, 0)) || (is_free(dx, -dy) && !is_free(0, -dy))
+            }
+            Movement::EightConnected if dx != 0 => {
+                (is_free(dx, 1) && !is_free(0, 1)) || (is_free(dx, -1) && !is_free(0, -1))
+            }
+            Movement::EightConnected => {
+                (is_free(1, dy) && !is_free(1, 0)) || (is_free(-1, dy) && !is_free(-1, 0))
+            }
+            _ if dx != 0 && dy != 0 => false,
+            _ if dx != 0 => {
+                (is_free(0, 1) && !is_free(-dx, 1)) || (is_free(0, -1) && !is_free(-dx, -1))
+            }
+            _ => (is_free(1, 0) && !is_free(1, -dy)) || (is_free(-1, 0) && !is_free(-1, -dy)),
+        }
+    }
+
+    //the position reached by moving once in a direction, if that move is allowed
+    fn step(&self, pos: (usize, usize), (dx, dy): (isize, isize)) -> Option<(usize, usize)> {
+        if !self.is_traversable_at(pos, (dx, dy)) {
+            return None;
+        }
+        let next = Direction::from_delta((dx, dy))?.apply(pos)?;
+        if dx != 0 && dy != 0 && !self.can_move_diagonally(pos, next) {
+            return None;
+        }
+        Some(next)
+    }
+
+    fn is_traversable_at(&self, pos: (usize, usize), delta: (isize, isize)) -> bool {
+        match Direction::from_delta(delta).and_then(|dir| dir.apply(pos)) {
+            Some(pos) => self.is_traversable(pos),
+            None => false,
+        }
+    }
+}
+
+fn get_jump_direction(from: (usize, usize), to: (usize, usize)) -> (isize, isize) {
+    (
+        (to.0 as isize - from.0 as isize).signum(),
+        (to.1 as isize - from.1 as isize).signum(),
+    )
+}
+
+//jumps go in a straight line over cells that cost one
+fn get_jump_cost(from: (usize, usize), to: (usize, usize)) -> f32 {
+    let dx = max(from.0, to.0) - min(from.0, to.0);
+    let dy = max(from.1, to.1) - min(from.1, to.1);
+    max(dx, dy) as f32 + (std::f32::consts::SQRT_2 - 1.0) * min(dx, dy) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{assert_same_cost, get_best_cost, get_random_grids};
+    use crate::heuristic::Octile;
+    use crate::{Grid, Movement};
+
+    const MOVEMENTS: [Movement; 3] = [
+        Movement::FourConnected,
+        Movement::EightConnected,
+        Movement::EightConnectedNoCornerCutting,
+    ];
+
+    //a line for every row where X are walls, every cell costs one
+    fn get_grid(map: &str, movement: Movement) -> Grid {
+        let rows: Vec<&str> = map.lines().collect();
+        let mut grid = Grid::new(rows.len(), rows[0].len());
+        grid.set_movement(movement);
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                if c == 'X' {
+                    grid.set_wall(x, y);
+                }
+            }
+        }
+        grid
+    }
+
+    fn get_cost(grid: &Grid, start: (usize, usize), goal: (usize, usize)) -> Option<f32> {
+        grid.search(start, goal, &Octile)
+            .into_path()
+            .map(|path| path.get_cost())
+    }
+
+    //moving right along the bottom row, the cell above the end of the wall can't be reached
+    //any better from the left, so the jump stops next to it
+    #[test]
+    fn stops_next_to_the_end_of_a_wall() {
+        for &movement in &MOVEMENTS {
+            let grid = get_grid("..X...\n..X...\n......", movement);
+            //with diagonal moves it's reached from the cell below the wall
+            let forced = if movement == Movement::EightConnected {
+                (2, 2)
+            } else {
+                (3, 2)
+            };
+            for x in 1..grid.n() {
+                assert_eq!(
+                    grid.has_forced_neighbour((x, 2), (1, 0)),
+                    (x, 2) == forced,
+                    "{:?} at {:?}",
+                    movement,
+                    (x, 2)
+                );
+            }
+            let path = grid
+                .jump_point_search((0, 1), (5, 0), &Octile)
+                .into_path()
+                .unwrap();
+            let expected = get_cost(&grid, (0, 1), (5, 0)).unwrap();
+            assert!((path.get_cost() - expected).abs() < 1e-4, "{:?}", movement);
+        }
+    }
+
+    #[test]
+    fn finds_paths_as_cheap_as_a_star_between_every_pair_of_cells() {
+        let map = "\
+....X...
+.XX.X.X.
+..X...X.
+X.XXX.X.
+........
+.X.X.XX.";
+        for &movement in &MOVEMENTS {
+            let grid = get_grid(map, movement);
+            let cells: Vec<(usize, usize)> = (0..grid.m())
+                .flat_map(|y| (0..grid.n()).map(move |x| (x, y)))
+                .filter(|&pos| grid.is_traversable(pos))
+                .collect();
+            for &start in &cells {
+                for &goal in &cells {
+                    let found = grid
+                        .jump_point_search(start, goal, &Octile)
+                        .into_path()
+                        .map(|path| path.get_cost());
+                    let expected = get_cost(&grid, start, goal);
+                    assert!(
+                        (found.unwrap() - expected.unwrap()).abs() < 1e-4,
+                        "{:?} from {:?} to {:?}: expected {:?}, found {:?}",
+                        movement,
+                        start,
+                        goal,
+                        expected,
+                        found
+                    );
+                }
+            }
+        }
+    }
+
+    //the weighted grids are left out, as the search falls back to A* on them
+    #[test]
+    fn finds_paths_as_cheap_as_a_star() {
+        for grid in get_random_grids()
+            .into_iter()
+            .filter(|grid| grid.is_uniform())
+        {
+            let (car, goal) = (grid.get_car().unwrap(), grid.get_goal().unwrap());
+            let result = grid.jump_point_search(car, goal, &Octile);
+            assert_same_cost(
+                &grid,
+                get_best_cost(&grid),
+                result.get_path().map(|path| path.get_cost()),
+            );
+        }
+    }
+}
@@ -0,0 +1,429 @@
+use super::content::{Content, Direction};
+use super::{Grid, Heuristic};
+use fxhash::FxHashMap;
+use std::cmp::Ordering;
+mod a_star;
+mod bidirectional;
+mod d_star_lite;
+mod jump_point_search;
+pub use a_star::AStarSearch;
+pub use d_star_lite::DStarLite;
+
+#[derive(Copy, Clone)]
+struct AStarNode {
+    pos: (usize, usize),
+    predecessor: Option<(usize, usize)>,
+    dist: f32,
+    guessed_dist: f32,
+    depth: usize,
+}
+
+impl Default for AStarNode {
+    fn default() -> Self {
+        AStarNode {
+            pos: (0, 0),
+            predecessor: None,
+            dist: f32::MAX,
+            guessed_dist: f32::MAX,
+            depth: 0,
+        }
+    }
+}
+
+impl Default for &AStarNode {
+    fn default() -> Self {
+        &AStarNode {
+            pos: (0, 0),
+            predecessor: None,
+            dist: f32::MAX,
+            guessed_dist: f32::MAX,
+            depth: 0,
+        }
+    }
+}
+
+impl Eq for AStarNode {}
+
+impl PartialEq for AStarNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.guessed_dist == other.guessed_dist && self.depth == other.depth
+    }
+}
+
+impl Ord for AStarNode {
+    //They get compared in inversed order
+    //so an AStarNode will have more priority if its guessed distance is smaller
+    //if equal the node with the higher depth will have more priority
+    fn cmp(&self, other: &Self) -> Ordering {
+        //this will panic if guessed_dist is NaN. That shouldn't happen and the floating point
+        //is needed to use heuristics like the euclidean distance
+        match other.guessed_dist.partial_cmp(&self.guessed_dist).unwrap() {
+            Ordering::Equal => {
+                if other.depth < self.depth {
+                    Ordering::Greater
+                } else {
+                    Ordering::Less
+                }
+            }
+            x => x,
+        }
+    }
+}
+
+impl PartialOrd for AStarNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+//how the car is allowed to move between cells
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Movement {
+    //only to the north, south, east and west neighbours
+    #[default]
+    FourConnected,
+    //also to the diagonal neighbours, even squeezing between two walls
+    EightConnected,
+    //also to the diagonal neighbours, but only if neither of the cells next to both is a wall
+    EightConnectedNoCornerCutting,
+}
+
+pub struct PathResult {
+    explored: usize,
+    path: Vec<(usize, usize)>,
+    directions: Vec<Direction>,
+    cost: f32,
+}
+
+impl PathResult {
+    fn new(
+        node_map: &FxHashMap<(usize, usize), AStarNode>,
+        start: (usize, usize),
+        end: (usize, usize),
+        explored: usize,
+    ) -> Self {
+        let mut path = vec![end];
+        let mut current = end;
+        while current != start {
+            let next = current;
+            current = node_map[&current].predecessor.unwrap();
+            //predecessors can be further away than a cell when jumping, like in jump point search
+            //the jumps are always in a straight line, so the cells in between are filled
+            let mut between = next;
+            loop {
+                between = (
+                    step_towards(between.0, current.0),
+                    step_towards(between.1, current.1),
+                );
+                if between == current {
+                    break;
+                }
+                path.push(between);
+            }
+            path.push(current);
+        }
+        path.reverse();
+        PathResult::from_path(path, node_map[&end].dist, explored)
+    }
+
+    fn from_path(path: Vec<(usize, usize)>, cost: f32, explored: usize) -> Self {
+        let directions = path
+            .windows(2)
+            .map(|step| Direction::between(step[0], step[1]).expect("Corrupted predecessors table"))
+            .collect();
+        PathResult {
+            explored,
+            path,
+            directions,
+            cost,
+        }
+    }
+
+    //the positions of the path in order, from the car to the goal, both included
+    pub fn get_path(&self) -> &[(usize, usize)] {
+        &self.path
+    }
+
+    //the moves needed to follow the path from the car to the goal
+    pub fn get_directions(&self) -> &[Direction] {
+        &self.directions
+    }
+
+    //the number of moves, one for every cell of the path after the car
+    pub fn get_path_length(&self) -> usize {
+        self.directions.len()
+    }
+
+    pub fn get_cost(&self) -> f32 {
+        self.cost
+    }
+
+    //the same as the one of the search that found the path
+    pub fn get_n_explored(&self) -> usize {
+        self.explored
+    }
+}
+
+pub struct SearchResult {
+    explored: Vec<(usize, usize)>,
+    explored_backward: Vec<(usize, usize)>,
+    n_explored: usize,
+    path: Option<PathResult>,
+}
+
+impl SearchResult {
+    //the expanded cells in the order they were expanded, without the start and the goal
+    pub fn get_explored(&self) -> &[(usize, usize)] {
+        &self.explored
+    }
+
+    //the cells expanded from the goal, only the searches in both directions have them
+    pub fn get_explored_backward(&self) -> &[(usize, usize)] {
+        &self.explored_backward
+    }
+
+    //the number of expansions, counting the start and the goal, with or without a path
+    pub fn get_n_explored(&self) -> usize {
+        self.n_explored
+    }
+
+    pub fn get_path(&self) -> Option<&PathResult> {
+        self.path.as_ref()
+    }
+
+    pub fn into_path(self) -> Option<PathResult> {
+        self.path
+    }
+}
+
+fn step_towards(from: usize, to: usize) -> usize {
+    match from.cmp(&to) {
+        Ordering::Less => from + 1,
+        Ordering::Equal => from,
+        Ordering::Greater => from - 1,
+    }
+}
+
+impl Grid {
+    //searches a path from the car to the goal and draws it in the grid
+    pub fn find_path<H: Heuristic + ?Sized>(&mut self, heuristic: &H) -> Option<PathResult> {
+        assert!(self.car.is_some() && self.goal.is_some());
+        let result = self.search(self.car.unwrap(), self.goal.unwrap(), heuristic);
+        self.draw_search(&result);
+        result.into_path()
+    }
+
+    //searches a path between two positions without modifying the grid
+    pub fn search<H: Heuristic + ?Sized>(
+        &self,
+        start: (usize, usize),
+        goal: (usize, usize),
+        heuristic: &H,
+    ) -> SearchResult {
+        self.search_steps(start, goal, heuristic).into_result()
+    }
+
+    //draws the explored cells and the path of a search in the grid
+    pub fn draw_search(&mut self, result: &SearchResult) {
+        for &pos in result.get_explored() {
+            self.draw_mark(pos, Content::Explored);
+        }
+        for &pos in result.get_explored_backward() {
+            self.draw_mark(pos, Content::ExploredBackward);
+        }
+        if let Some(path) = result.get_path() {
+            self.draw_path(path);
+        }
+    }
+
+    pub fn draw_path(&mut self, result: &PathResult) {
+        self.draw_trajectory(result.get_path());
+    }
+
+    //every position but the first and the last gets the direction of the move that leaves it,
+    //so the first one, where the car starts, and the last one, the goal, aren't drawn over
+    pub fn draw_trajectory(&mut self, trajectory: &[(usize, usize)]) {
+        for step in trajectory.windows(2).skip(1) {
+            let dir = Direction::between(step[0], step[1]).expect("The positions aren't adjacent");
+            self.draw_mark(step[0], Content::Trace(dir));
+        }
+    }
+
+    //the car, the goal and the walls are never drawn over
+    fn draw_mark(&mut self, pos: (usize, usize), mark: Content) {
+        if !matches!(
+            self.grid[pos.1][pos.0],
+            Content::Car | Content::Goal | Content::Wall
+        ) {
+            self.marks[pos.1][pos.0] = Some(mark);
+        }
+    }
+
+    //diagonal moves cost sqrt(2) times the cost of the cell they go into
+    pub(crate) fn get_move_cost(&self, from: (usize, usize), to: (usize, usize)) -> f32 {
+        let cost = self
+            .get_terrain(to)
+            .get_cost()
+            .expect("Walls can't be traversed");
+        if from.0 != to.0 && from.1 != to.1 {
+            cost * std::f32::consts::SQRT_2
+        } else {
+            cost
+        }
+    }
+
+    fn get_neighbours(&self, pos: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut neighs: Vec<(usize, usize)> = Direction::ORTHOGONAL
+            .iter()
+            .filter_map(|dir| dir.apply(pos))
+            .filter(|&neigh| self.is_traversable(neigh))
+            .collect();
+        if self.movement != Movement::FourConnected {
+            neighs.extend(
+                Direction::DIAGONAL
+                    .iter()
+                    .filter_map(|dir| dir.apply(pos))
+                    .filter(|&neigh| self.can_move_diagonally(pos, neigh)),
+            );
+        }
+        neighs
+    }
+
+    fn can_move_diagonally(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        if !self.is_traversable(to) {
+            return false;
+        }
+        match self.movement {
+            Movement::FourConnected => false,
+            Movement::EightConnected => true,
+            Movement::EightConnectedNoCornerCutting => {
+                self.is_traversable((to.0, from.1)) && self.is_traversable((from.0, to.1))
+            }
+        }
+    }
+
+    //inside the grid and not a wall
+    pub fn is_traversable(&self, pos: (usize, usize)) -> bool {
+        self.is_inside(pos) && self.get_terrain(pos).get_cost().is_some()
+    }
+
+    //whether there is any path between the positions, which is cheaper to know than the best one
+    pub fn is_connected(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        assert!(self.is_inside(from) && self.is_inside(to));
+        let mut found = false;
+        self.flood_fill(from, |pos| {
+            found = pos == to;
+            !found
+        });
+        found
+    }
+
+    //the cells that can be reached from a position, including it
+    pub fn get_reachable_cells(&self, from: (usize, usize)) -> Vec<(usize, usize)> {
+        assert!(self.is_inside(from));
+        let mut reachable = Vec::new();
+        self.flood_fill(from, |pos| {
+            reachable.push(pos);
+            true
+        });
+        reachable
+    }
+
+    //visits the cells reachable from a position until visit returns false
+    fn flood_fill<F: FnMut((usize, usize)) -> bool>(&self, from: (usize, usize), mut visit: F) {
+        let mut visited = vec![vec![false; self.n()]; self.m()];
+        visited[from.1][from.0] = true;
+        let mut stack = vec![from];
+        while let Some(pos) = stack.pop() {
+            if !visit(pos) {
+                return;
+            }
+            for neigh in self.get_neighbours(pos) {
+                if !visited[neigh.1][neigh.0] {
+                    visited[neigh.1][neigh.0] = true;
+                    stack.push(neigh);
+                }
+            }
+        }
+    }
+
+    pub fn clear_path(&mut self) {
+        for mark in self.marks.iter_mut().flat_map(|row| row.iter_mut()) {
+            *mark = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Movement;
+    use crate::content::Content;
+    use crate::heuristic::Octile;
+    use crate::Grid;
+
+    //grids with random walls for every movement, half of them with weighted cells too
+    pub(super) fn get_random_grids() -> Vec<Grid> {
+        let mut grids = Vec::new();
+        for &movement in &[
+            Movement::FourConnected,
+            Movement::EightConnected,
+            Movement::EightConnectedNoCornerCutting,
+        ] {
+            for seed in 0..100 {
+                let mut grid = Grid::new(12, 16);
+                grid.fill_random_with_seed(30, seed);
+                grid.set_movement(movement);
+                if seed % 2 == 0 {
+                    for y in 0..grid.m() {
+                        for x in 0..grid.n() {
+                            if (x * 7 + y * 3 + seed as usize).is_multiple_of(5)
+                                && grid.grid[y][x] == Content::Empty
+                            {
+                                grid.set_weighted(x, y, ((x + y) % 8 + 2) as u8);
+                            }
+                        }
+                    }
+                }
+                if grid.has_car() && grid.has_goal() {
+                    grids.push(grid);
+                }
+            }
+        }
+        grids
+    }
+
+    //the octile distance never overestimates with any movement, so find_path gives the best cost
+    pub(super) fn get_best_cost(grid: &Grid) -> Option<f32> {
+        grid.clone().find_path(&Octile).map(|path| path.get_cost())
+    }
+
+    pub(super) fn assert_same_cost(grid: &Grid, expected: Option<f32>, found: Option<f32>) {
+        match (expected, found) {
+            (Some(expected), Some(found)) => assert!(
+                (expected - found).abs() < 1e-3,
+                "expected a cost of {}, found {} in\n{}",
+                expected,
+                found,
+                grid.to_map_string()
+            ),
+            (None, None) => {}
+            _ => panic!(
+                "expected {:?}, found {:?} in\n{}",
+                expected,
+                found,
+                grid.to_map_string()
+            ),
+        }
+    }
+
+    #[test]
+    fn the_length_of_a_path_is_its_number_of_moves() {
+        let mut grid = Grid::new(3, 5);
+        grid.set_movement(Movement::EightConnected);
+        let path = grid.search((0, 0), (4, 2), &Octile).into_path().unwrap();
+        assert_eq!(path.get_path().len(), 5);
+        assert_eq!(path.get_path_length(), 4);
+        let path = grid.search((1, 1), (1, 1), &Octile).into_path().unwrap();
+        assert_eq!(path.get_path_length(), 0);
+    }
+}
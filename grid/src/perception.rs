@@ -0,0 +1,67 @@
+use super::content::{Content, Direction};
+use super::Grid;
+use std::fmt;
+
+//the proximity sensors of the car, one for each direction it can move to
+//a sensor is on if the neighbour in its direction is a wall or outside of the grid
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Perception {
+    pub north: bool,
+    pub west: bool,
+    pub south: bool,
+    pub east: bool,
+}
+
+impl Perception {
+    //the sensors in the order SN, SO, SS, SE
+    pub fn to_vector(&self) -> [bool; 4] {
+        [self.north, self.west, self.south, self.east]
+    }
+
+    //None for the diagonal directions, which don't have a sensor
+    pub fn is_occupied(&self, dir: Direction) -> Option<bool> {
+        match dir {
+            Direction::Up => Some(self.north),
+            Direction::Left => Some(self.west),
+            Direction::Down => Some(self.south),
+            Direction::Right => Some(self.east),
+            Direction::UpLeft | Direction::UpRight | Direction::DownLeft | Direction::DownRight => {
+                None
+            }
+        }
+    }
+}
+
+impl fmt::Display for Perception {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "SN={} SO={} SS={} SE={}",
+            self.north as u8, self.west as u8, self.south as u8, self.east as u8
+        )
+    }
+}
+
+impl Grid {
+    //the readings of the sensors in the position of the car, None if there isn't a car
+    pub fn get_perception(&self) -> Option<Perception> {
+        self.car.map(|pos| self.get_perception_at(pos))
+    }
+
+    //the readings the sensors would have if the car was in pos
+    pub fn get_perception_at(&self, pos: (usize, usize)) -> Perception {
+        assert!(self.is_inside(pos));
+        let is_occupied = |dir: Direction| match dir.apply(pos) {
+            Some(neigh) if self.is_inside(neigh) => {
+                matches!(self.grid[neigh.1][neigh.0], Content::Wall)
+            }
+            _ => true,
+        };
+        Perception {
+            north: is_occupied(Direction::Up),
+            west: is_occupied(Direction::Left),
+            south: is_occupied(Direction::Down),
+            east: is_occupied(Direction::Right),
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use super::content::Content;
+use super::{get_seeded_rng, Grid};
+use rand::{thread_rng, Rng};
+use std::cmp::{max, min};
+use std::ops::RangeInclusive;
+
+//how the rooms of a rooms and corridors map are generated
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoomRules {
+    //the most rooms that are tried to be placed, the ones that would overlap are discarded
+    pub max_rooms: usize,
+    pub width: RangeInclusive<usize>,
+    pub height: RangeInclusive<usize>,
+}
+
+impl Default for RoomRules {
+    fn default() -> Self {
+        RoomRules {
+            max_rooms: 12,
+            width: 4..=10,
+            height: 3..=6,
+        }
+    }
+}
+
+//the first and the last cell of a room
+type Room = ((usize, usize), (usize, usize));
+
+impl Grid {
+    pub fn fill_rooms(&mut self, rules: &RoomRules) {
+        self.fill_rooms_with_rng(rules, &mut thread_rng());
+    }
+
+    pub fn fill_rooms_with_seed(&mut self, rules: &RoomRules, seed: u64) {
+        self.fill_rooms_with_rng(rules, &mut get_seeded_rng(seed));
+    }
+
+    //every room is joined with the previous one by a corridor, so all of them are connected
+    //the rooms are shrunk to fit in the grid if they're bigger than it
+    pub fn fill_rooms_with_rng<R: Rng + ?Sized>(&mut self, rules: &RoomRules, rng: &mut R) {
+        assert!(*rules.width.start() != 0 && !rules.width.is_empty());
+        assert!(*rules.height.start() != 0 && !rules.height.is_empty());
+        self.car = None;
+        self.goal = None;
+        self.clear_path();
+        for cell in self.grid.iter_mut().flatten() {
+            *cell = Content::Wall;
+        }
+        let mut rooms: Vec<Room> = Vec::new();
+        for _ in 0..rules.max_rooms {
+            let width = min(
+                rng.gen_range(*rules.width.start(), rules.width.end() + 1),
+                self.n(),
+            );
+            let height = min(
+                rng.gen_range(*rules.height.start(), rules.height.end() + 1),
+                self.m(),
+            );
+            let x = rng.gen_range(0, self.n() - width + 1);
+            let y = rng.gen_range(0, self.m() - height + 1);
+            let room = ((x, y), (x + width - 1, y + height - 1));
+            //the rooms are kept a cell apart so they don't merge
+            if rooms.iter().all(|other| !are_close(&room, other)) {
+                rooms.push(room);
+            }
+        }
+        for (i, &((x0, y0), (x1, y1))) in rooms.iter().enumerate() {
+            for row in &mut self.grid[y0..=y1] {
+                for cell in &mut row[x0..=x1] {
+                    *cell = Content::Empty;
+                }
+            }
+            if i > 0 {
+                self.dig_corridor(get_center(&rooms[i - 1]), get_center(&rooms[i]), rng);
+            }
+        }
+        self.place_connected_car_and_goal(rng);
+    }
+
+    //an L shaped corridor, which turns at one of the two possible corners
+    fn dig_corridor<R: Rng + ?Sized>(
+        &mut self,
+        from: (usize, usize),
+        to: (usize, usize),
+        rng: &mut R,
+    ) {
+        let corner = if rng.gen() {
+            (to.0, from.1)
+        } else {
+            (from.0, to.1)
+        };
+        for &(start, end) in &[(from, corner), (corner, to)] {
+            for y in min(start.1, end.1)..=max(start.1, end.1) {
+                for x in min(start.0, end.0)..=max(start.0, end.0) {
+                    self.grid[y][x] = Content::Empty;
+                }
+            }
+        }
+    }
+}
+
+fn are_close(room: &Room, other: &Room) -> bool {
+    let ((x0, y0), (x1, y1)) = *room;
+    let ((other_x0, other_y0), (other_x1, other_y1)) = *other;
+    x0 <= other_x1 + 1 && other_x0 <= x1 + 1 && y0 <= other_y1 + 1 && other_y0 <= y1 + 1
+}
+
+fn get_center(room: &Room) -> (usize, usize) {
+    let ((x0, y0), (x1, y1)) = *room;
+    ((x0 + x1) / 2, (y0 + y1) / 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RoomRules;
+    use crate::Grid;
+
+    #[test]
+    fn the_car_and_the_goal_are_connected() {
+        for seed in 0..50 {
+            let mut grid = Grid::new(20, 30);
+            grid.fill_rooms_with_seed(&RoomRules::default(), seed);
+            let (car, goal) = (grid.get_car().unwrap(), grid.get_goal().unwrap());
+            assert!(grid.is_connected(car, goal), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn a_seed_always_generates_the_same_rooms() {
+        let mut grid = Grid::new(20, 30);
+        grid.fill_rooms_with_seed(&RoomRules::default(), 7);
+        let mut other = Grid::new(20, 30);
+        other.fill_rooms_with_seed(&RoomRules::default(), 7);
+        assert_eq!(grid.to_map_string(), other.to_map_string());
+        other.fill_rooms_with_seed(&RoomRules::default(), 8);
+        assert_ne!(grid.to_map_string(), other.to_map_string());
+    }
+
+    #[test]
+    fn a_room_has_a_size_in_the_ranges() {
+        let rules = RoomRules {
+            max_rooms: 1,
+            width: 3..=3,
+            height: 2..=2,
+        };
+        let mut grid = Grid::new(10, 10);
+        grid.fill_rooms_with_seed(&rules, 0);
+        assert_eq!(grid.get_reachable_cells(grid.get_car().unwrap()).len(), 6);
+    }
+}
@@ -0,0 +1,200 @@
+use super::content::{Content, Direction, MAX_WEIGHT};
+use crossterm::style::{style, Color, StyledContent};
+
+//the colors and the glyphs used to draw the grid
+//the car, the goal and the path are glyphs drawn over the color of the empty cells, so their
+//colors are the ones of the glyphs. They're also used for the blocks shown when zooming out
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub border: Color,
+    pub wall: Color,
+    pub empty: Color,
+    //the colors of the costs from 2 to MAX_WEIGHT
+    pub weighted: [Color; MAX_WEIGHT as usize - 1],
+    pub explored: Color,
+    pub explored_backward: Color,
+    pub open: Color,
+    pub current: Color,
+    pub car: Color,
+    pub goal: Color,
+    pub trace: Color,
+    //the glyphs must be two columns wide, like the cells
+    pub car_glyph: String,
+    pub goal_glyph: String,
+    //in the order left, up, right, down, up left, up right, down left and down right
+    pub trace_glyphs: [String; 8],
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            border: Color::DarkBlue,
+            wall: Color::Red,
+            empty: Color::Black,
+            //the higher the cost the lighter the cell
+            weighted: get_grays(232),
+            explored: Color::Yellow,
+            explored_backward: Color::DarkCyan,
+            open: Color::Green,
+            current: Color::Magenta,
+            car: Color::Cyan,
+            goal: Color::White,
+            trace: Color::Grey,
+            car_glyph: String::from("🚗"),
+            goal_glyph: String::from("🏁"),
+            trace_glyphs: get_arrows(),
+        }
+    }
+}
+
+impl Theme {
+    //white walls and dark searches, so the path and the car stand out
+    pub fn high_contrast() -> Self {
+        Theme {
+            border: Color::DarkGrey,
+            wall: Color::White,
+            empty: Color::Black,
+            weighted: get_grays(234),
+            explored: Color::DarkYellow,
+            explored_backward: Color::DarkCyan,
+            open: Color::DarkGreen,
+            current: Color::DarkMagenta,
+            car: Color::Cyan,
+            goal: Color::Green,
+            trace: Color::Yellow,
+            ..Theme::default()
+        }
+    }
+
+    //the Okabe-Ito palette, which can be told apart with the usual kinds of color blindness
+    pub fn color_blind_safe() -> Self {
+        Theme {
+            border: Color::Rgb {
+                r: 128,
+                g: 128,
+                b: 128,
+            },
+            wall: Color::Rgb {
+                r: 0,
+                g: 114,
+                b: 178,
+            },
+            empty: Color::Black,
+            explored: Color::Rgb {
+                r: 230,
+                g: 159,
+                b: 0,
+            },
+            explored_backward: Color::Rgb {
+                r: 86,
+                g: 180,
+                b: 233,
+            },
+            open: Color::Rgb {
+                r: 0,
+                g: 158,
+                b: 115,
+            },
+            current: Color::Rgb {
+                r: 204,
+                g: 121,
+                b: 167,
+            },
+            car: Color::White,
+            goal: Color::Rgb {
+                r: 213,
+                g: 94,
+                b: 0,
+            },
+            trace: Color::Rgb {
+                r: 240,
+                g: 228,
+                b: 66,
+            },
+            ..Theme::default()
+        }
+    }
+
+    //for the terminals that can't draw the emojis two columns wide
+    pub fn use_ascii_glyphs(&mut self) {
+        self.car_glyph = String::from(ASCII_CAR);
+        self.goal_glyph = String::from(ASCII_GOAL);
+    }
+
+    //replaces the glyphs that the function given says aren't drawn two columns wide with ones
+    //made of ascii characters, which always are. Returns if any glyph was replaced
+    pub fn replace_glyphs<F: FnMut(&str) -> bool>(&mut self, mut is_two_columns: F) -> bool {
+        let mut replaced = false;
+        let glyphs = std::iter::once((&mut self.car_glyph, &ASCII_CAR))
+            .chain(std::iter::once((&mut self.goal_glyph, &ASCII_GOAL)))
+            .chain(self.trace_glyphs.iter_mut().zip(ASCII_TRACE.iter()));
+        for (glyph, ascii) in glyphs {
+            if !is_two_columns(glyph) {
+                *glyph = String::from(*ascii);
+                replaced = true;
+            }
+        }
+        replaced
+    }
+
+    pub(crate) fn draw(&self, content: Content) -> StyledContent<&str> {
+        match content {
+            Content::Car => style(self.car_glyph.as_str()).with(self.car).on(self.empty),
+            Content::Goal => style(self.goal_glyph.as_str())
+                .with(self.goal)
+                .on(self.empty),
+            Content::Trace(dir) => style(self.get_trace_glyph(dir))
+                .with(self.trace)
+                .on(self.empty),
+            content => style("  ").on(self.get_block_color(content)),
+        }
+    }
+
+    //the color of the cell when it's drawn in half a character, which can't hold its glyph
+    pub(crate) fn get_block_color(&self, content: Content) -> Color {
+        match content {
+            Content::Car => self.car,
+            Content::Goal => self.goal,
+            Content::Wall => self.wall,
+            Content::Empty => self.empty,
+            Content::Weighted(weight) => self.weighted[weight as usize - 2],
+            Content::Trace(_) => self.trace,
+            Content::Explored => self.explored,
+            Content::ExploredBackward => self.explored_backward,
+            Content::Open => self.open,
+            Content::Current => self.current,
+        }
+    }
+
+    fn get_trace_glyph(&self, dir: Direction) -> &str {
+        let i = match dir {
+            Direction::Left => 0,
+            Direction::Up => 1,
+            Direction::Right => 2,
+            Direction::Down => 3,
+            Direction::UpLeft => 4,
+            Direction::UpRight => 5,
+            Direction::DownLeft => 6,
+            Direction::DownRight => 7,
+        };
+        &self.trace_glyphs[i]
+    }
+}
+
+const ASCII_CAR: &str = "CC";
+const ASCII_GOAL: &str = "GG";
+//in the same order as the trace glyphs
+const ASCII_TRACE: [&str; 8] = ["<<", "^^", ">>", "vv", "\\\\", "//", "//", "\\\\"];
+
+//a gray for every cost, two steps lighter each time
+fn get_grays(first: u8) -> [Color; MAX_WEIGHT as usize - 1] {
+    let mut grays = [Color::Black; MAX_WEIGHT as usize - 1];
+    for (weight, gray) in (2..=MAX_WEIGHT).zip(grays.iter_mut()) {
+        *gray = Color::AnsiValue(first + 2 * weight);
+    }
+    grays
+}
+
+fn get_arrows() -> [String; 8] {
+    ["←←", "↑↑", "→→", "↓↓", "↖↖", "↗↗", "↙↙", "↘↘"].map(String::from)
+}
@@ -0,0 +1,253 @@
+use super::content::Content;
+use super::{Grid, Theme};
+use crossterm::style::{style, Color};
+use std::cmp::min;
+
+//the columns and rows of the terminal taken by the border around the cells
+const BORDER_COLUMNS: usize = 4;
+const BORDER_ROWS: usize = 2;
+//the zoom out at which a block of cells is as big as the biggest grid there can be
+const MAX_ZOOM_OUT: u32 = usize::BITS;
+
+//the part of the grid that is drawn in an area of the terminal
+//without zoom every cell is two characters wide. When zoomed out every character is split
+//in two halves and each half shows a square block of cells
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Viewport {
+    //the first cell shown
+    pub x: usize,
+    pub y: usize,
+    //0 shows every cell, n shows blocks of 2^(n-1) x 2^(n-1) cells
+    pub zoom_out: u32,
+}
+
+impl Viewport {
+    //the side of the blocks of cells that are shown together
+    pub fn get_scale(&self) -> usize {
+        if self.zoom_out == 0 {
+            1
+        } else {
+            1 << (self.zoom_out - 1)
+        }
+    }
+
+    //the cells that fit in an area of the terminal, given as its columns and rows
+    pub fn get_capacity(&self, area: (usize, usize)) -> (usize, usize) {
+        let columns = area.0.saturating_sub(BORDER_COLUMNS);
+        let rows = area.1.saturating_sub(BORDER_ROWS);
+        if self.zoom_out == 0 {
+            (columns / 2, rows)
+        } else {
+            (
+                columns.saturating_mul(self.get_scale()),
+                rows.saturating_mul(2 * self.get_scale()),
+            )
+        }
+    }
+
+    //the cells of the grid that are shown, which may be less than the capacity near its end
+    pub fn get_shown_size(&self, grid: &Grid, area: (usize, usize)) -> (usize, usize) {
+        let capacity = self.get_capacity(area);
+        (
+            min(capacity.0, grid.n().saturating_sub(self.x)),
+            min(capacity.1, grid.m().saturating_sub(self.y)),
+        )
+    }
+
+    //moves the view the given number of cells, without leaving the grid
+    pub fn pan(&mut self, grid: &Grid, area: (usize, usize), dx: isize, dy: isize) {
+        self.x = (self.x as isize + dx).max(0) as usize;
+        self.y = (self.y as isize + dy).max(0) as usize;
+        self.clamp(grid, area);
+    }
+
+    //the center of the view stays in the same cell
+    pub fn zoom_in(&mut self, grid: &Grid, area: (usize, usize)) {
+        if self.zoom_out > 0 {
+            self.set_zoom_out(grid, area, self.zoom_out - 1);
+        }
+    }
+
+    //it doesn't zoom out further than needed to show the whole grid
+    pub fn zoom_out(&mut self, grid: &Grid, area: (usize, usize)) {
+        let capacity = self.get_capacity(area);
+        if (capacity.0 < grid.n() || capacity.1 < grid.m()) && self.zoom_out < MAX_ZOOM_OUT {
+            self.set_zoom_out(grid, area, self.zoom_out + 1);
+        }
+    }
+
+    fn set_zoom_out(&mut self, grid: &Grid, area: (usize, usize), zoom_out: u32) {
+        let shown = self.get_shown_size(grid, area);
+        let center = (self.x + shown.0 / 2, self.y + shown.1 / 2);
+        self.zoom_out = zoom_out;
+        let capacity = self.get_capacity(area);
+        self.x = center.0.saturating_sub(capacity.0 / 2);
+        self.y = center.1.saturating_sub(capacity.1 / 2);
+        self.clamp(grid, area);
+    }
+
+    //keeps the view inside the grid, which may have been resized, and shows as much of it as possible
+    pub fn clamp(&mut self, grid: &Grid, area: (usize, usize)) {
+        let capacity = self.get_capacity(area);
+        self.x = min(self.x, grid.n().saturating_sub(capacity.0));
+        self.y = min(self.y, grid.m().saturating_sub(capacity.1));
+    }
+
+    //moves the view the least needed to show the cell
+    pub fn scroll_to(&mut self, grid: &Grid, area: (usize, usize), cell: (usize, usize)) {
+        let capacity = self.get_capacity(area);
+        if cell.0 < self.x {
+            self.x = cell.0;
+        } else if cell.0 >= self.x + capacity.0 {
+            self.x = cell.0 + 1 - capacity.0;
+        }
+        if cell.1 < self.y {
+            self.y = cell.1;
+        } else if cell.1 >= self.y + capacity.1 {
+            self.y = cell.1 + 1 - capacity.1;
+        }
+        self.clamp(grid, area);
+    }
+
+    //the position of the terminal where a cell is drawn, if it's shown
+    pub fn get_position(
+        &self,
+        grid: &Grid,
+        area: (usize, usize),
+        cell: (usize, usize),
+    ) -> Option<(usize, usize)> {
+        let shown = self.get_shown_size(grid, area);
+        if cell.0 < self.x || cell.1 < self.y {
+            return None;
+        }
+        let (dx, dy) = (cell.0 - self.x, cell.1 - self.y);
+        if dx >= shown.0 || dy >= shown.1 {
+            return None;
+        }
+        let (column, row) = if self.zoom_out == 0 {
+            (dx * 2, dy)
+        } else {
+            (dx / self.get_scale(), dy / (2 * self.get_scale()))
+        };
+        Some((column + BORDER_COLUMNS / 2, row + BORDER_ROWS / 2))
+    }
+
+    //the cell drawn in a position of the terminal, if there is one
+    //when zoomed out it's the first cell of the upper block of the character
+    pub fn get_cell(
+        &self,
+        grid: &Grid,
+        area: (usize, usize),
+        pos: (usize, usize),
+    ) -> Option<(usize, usize)> {
+        if pos.0 < BORDER_COLUMNS / 2 || pos.1 < BORDER_ROWS / 2 {
+            return None;
+        }
+        let (column, row) = (pos.0 - BORDER_COLUMNS / 2, pos.1 - BORDER_ROWS / 2);
+        let (dx, dy) = if self.zoom_out == 0 {
+            (column / 2, row)
+        } else {
+            (column * self.get_scale(), row * 2 * self.get_scale())
+        };
+        let shown = self.get_shown_size(grid, area);
+        if dx < shown.0 && dy < shown.1 {
+            Some((self.x + dx, self.y + dy))
+        } else {
+            None
+        }
+    }
+}
+
+impl Grid {
+    //draws the part of the grid in the view with a border around it, like the whole grid is displayed
+    pub fn draw_viewport(&self, view: &Viewport, area: (usize, usize), theme: &Theme) -> String {
+        let shown = view.get_shown_size(self, area);
+        if shown.0 == 0 || shown.1 == 0 {
+            return String::new();
+        }
+        let scale = view.get_scale();
+        let (columns, rows) = if view.zoom_out == 0 {
+            (shown.0 * 2, shown.1)
+        } else {
+            (shown.0.div_ceil(scale), shown.1.div_ceil(2 * scale))
+        };
+        let border_row = format!(
+            "{}\n\r",
+            style(" ".repeat(columns + BORDER_COLUMNS)).on(theme.border)
+        );
+        let side = style("  ").on(theme.border);
+        let mut view_str = border_row.clone();
+        for row in 0..rows {
+            view_str += &side.to_string();
+            if view.zoom_out == 0 {
+                let y = view.y + row;
+                for x in view.x..view.x + shown.0 {
+                    view_str += &theme
+                        .draw(self.marks[y][x].unwrap_or(self.grid[y][x]))
+                        .to_string();
+                }
+            } else {
+                let top = view.y + row * 2 * scale;
+                let bottom = top + scale;
+                for column in 0..columns {
+                    let x = view.x + column * scale;
+                    let top_color = self.get_block_color(view, shown, theme, (x, top));
+                    let bottom_color = self.get_block_color(view, shown, theme, (x, bottom));
+                    view_str += &style('▀').with(top_color).on(bottom_color).to_string();
+                }
+            }
+            view_str += &format!("{}\n\r", side);
+        }
+        view_str + &border_row
+    }
+
+    //the color of the most important content in the block, so the car, the goal and the path
+    //don't disappear when zooming out. The walls are only shown if they're most of the block
+    fn get_block_color(
+        &self,
+        view: &Viewport,
+        shown: (usize, usize),
+        theme: &Theme,
+        pos: (usize, usize),
+    ) -> Color {
+        let scale = view.get_scale();
+        let end = (
+            min(pos.0 + scale, view.x + shown.0),
+            min(pos.1 + scale, view.y + shown.1),
+        );
+        if pos.1 >= end.1 {
+            return theme.border;
+        }
+        let mut shown_content = None;
+        let mut n_walls = 0;
+        for y in pos.1..end.1 {
+            for x in pos.0..end.0 {
+                let content = self.marks[y][x].unwrap_or(self.grid[y][x]);
+                if let Content::Wall = content {
+                    n_walls += 1;
+                } else if get_priority(&content) > shown_content.as_ref().map_or(0, get_priority) {
+                    shown_content = Some(content);
+                }
+            }
+        }
+        let n_cells = (end.0 - pos.0) * (end.1 - pos.1);
+        match shown_content {
+            Some(content) => theme.get_block_color(content),
+            None if n_walls * 2 >= n_cells => theme.wall,
+            None => theme.empty,
+        }
+    }
+}
+
+fn get_priority(content: &Content) -> u8 {
+    match content {
+        Content::Car => 7,
+        Content::Goal => 6,
+        Content::Current => 5,
+        Content::Trace(_) => 4,
+        Content::Open => 3,
+        Content::Explored | Content::ExploredBackward => 2,
+        Content::Weighted(_) => 1,
+        Content::Wall | Content::Empty => 0,
+    }
+}